@@ -4,7 +4,8 @@ use quote::quote;
 use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
-    parse_macro_input, token, Data, Ident, LitStr, Token,
+    parse_macro_input, parse_quote, token, Data, Fields, Ident, Lit, LitStr, Meta, NestedMeta,
+    Token,
 };
 
 #[proc_macro_error]
@@ -41,6 +42,15 @@ struct VerifyOptions {
     serde_spans: Option<(Ident, TokenStream)>,
 
     is_schemars: Option<Ident>,
+    schemars_settings: Option<(Ident, TokenStream)>,
+
+    context: Option<(Ident, TokenStream)>,
+    // The path of the check, and the group pattern if it only applies to some groups.
+    checks: Vec<(Ident, TokenStream, Option<TokenStream>)>,
+    checks_with: Vec<(Ident, TokenStream)>,
+
+    group: Option<(Ident, TokenStream)>,
+    partial: Vec<(Ident, TokenStream)>,
 }
 
 impl VerifyOptions {
@@ -107,6 +117,37 @@ impl VerifyOptions {
 
             self.is_schemars = v.into();
         }
+
+        if let Some(v) = other.schemars_settings {
+            if let Some(existing_v) = &self.schemars_settings {
+                emit_error!(existing_v.0, "{} defined here", existing_v.0);
+                abort!(v.0, r#"duplicate keys "{}""#, v.0);
+            }
+
+            self.schemars_settings = v.into();
+        }
+
+        if let Some(v) = other.context {
+            if let Some(existing_v) = &self.context {
+                emit_error!(existing_v.0, "{} defined here", existing_v.0);
+                abort!(v.0, r#"duplicate keys "{}""#, v.0);
+            }
+
+            self.context = v.into();
+        }
+
+        if let Some(v) = other.group {
+            if let Some(existing_v) = &self.group {
+                emit_error!(existing_v.0, "{} defined here", existing_v.0);
+                abort!(v.0, r#"duplicate keys "{}""#, v.0);
+            }
+
+            self.group = v.into();
+        }
+
+        self.checks.extend(other.checks);
+        self.checks_with.extend(other.checks_with);
+        self.partial.extend(other.partial);
     }
 
     fn parse_serde_options(&mut self, content: ParseStream) -> syn::Result<()> {
@@ -128,6 +169,34 @@ impl VerifyOptions {
         Ok(())
     }
 
+    fn parse_schemars_options(&mut self, content: ParseStream) -> syn::Result<()> {
+        if content.is_empty() {
+            return Ok(());
+        }
+
+        let schemars_id: Ident = content.parse()?;
+
+        if schemars_id == "settings" {
+            content.parse::<Token![=]>()?;
+            let s = content.parse::<LitStr>()?;
+
+            // Settings that are provided by Schemars can be used by their names.
+            let ts: TokenStream = match s.value().as_str() {
+                "draft07" | "draft2019_09" | "openapi3" => {
+                    let settings = Ident::new(&s.value(), s.span());
+                    quote!(schemars::gen::SchemaSettings::#settings)
+                }
+                _ => s.parse()?,
+            };
+
+            self.schemars_settings = Some((schemars_id, ts));
+        } else {
+            abort!(schemars_id, r#"unknown schemars option "{}""#, schemars_id);
+        }
+
+        Ok(())
+    }
+
     fn parse_verifier_options(&mut self, content: ParseStream) -> syn::Result<()> {
         loop {
             if content.is_empty() {
@@ -161,6 +230,44 @@ impl VerifyOptions {
         }
     }
 
+    fn parse_check_options(&mut self, check_id: Ident, content: ParseStream) -> syn::Result<()> {
+        let mut path = None;
+        let mut group = None;
+
+        loop {
+            if content.is_empty() {
+                break;
+            }
+
+            let id: Ident = content.parse()?;
+
+            if id == "path" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                path = Some(ts);
+            } else if id == "group" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                group = Some(ts);
+            } else {
+                abort!(id, r#"unknown check option "{}""#, id);
+            }
+
+            if content.peek(Token![,]) {
+                content.parse::<Token!(,)>()?;
+            }
+        }
+
+        match path {
+            Some(path) => self.checks.push((check_id, path, group)),
+            None => abort!(check_id, "check path is required"),
+        }
+
+        Ok(())
+    }
+
     fn parse_option(&mut self, content: ParseStream) -> syn::Result<()> {
         let id = content.parse::<Ident>()?;
 
@@ -193,6 +300,59 @@ impl VerifyOptions {
 
         if id == "schemars" {
             self.is_schemars = Some(id);
+            if content.peek(token::Paren) {
+                let schemars_content;
+                parenthesized!(schemars_content in content);
+                self.parse_schemars_options(&schemars_content)?;
+            }
+
+            return Ok(());
+        }
+
+        if id == "context" {
+            content.parse::<Token![=]>()?;
+            let s = content.parse::<LitStr>()?;
+            let ts: TokenStream = s.parse()?;
+            self.context = Some((id, ts));
+            return Ok(());
+        }
+
+        if id == "check" {
+            if content.peek(token::Paren) {
+                let check_content;
+                parenthesized!(check_content in content);
+                self.parse_check_options(id, &check_content)?;
+            } else {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                self.checks.push((id, ts, None));
+            }
+
+            return Ok(());
+        }
+
+        if id == "group" {
+            content.parse::<Token![=]>()?;
+            let s = content.parse::<LitStr>()?;
+            let ts: TokenStream = s.parse()?;
+            self.group = Some((id, ts));
+            return Ok(());
+        }
+
+        if id == "partial" {
+            content.parse::<Token![=]>()?;
+            let s = content.parse::<LitStr>()?;
+            let ts: TokenStream = s.parse()?;
+            self.partial.push((id, ts));
+            return Ok(());
+        }
+
+        if id == "check_with" {
+            content.parse::<Token![=]>()?;
+            let s = content.parse::<LitStr>()?;
+            let ts: TokenStream = s.parse()?;
+            self.checks_with.push((id, ts));
             return Ok(());
         }
 
@@ -225,6 +385,143 @@ impl Parse for VerifyOptions {
     }
 }
 
+#[derive(Default)]
+struct FieldOptions {
+    async_checks: Vec<TokenStream>,
+    checks_with: Vec<TokenStream>,
+    // Group patterns.
+    required: Vec<TokenStream>,
+    forbidden: Vec<TokenStream>,
+}
+
+impl Parse for FieldOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut opts = FieldOptions::default();
+
+        if !input.peek(token::Paren) {
+            return Ok(opts);
+        }
+        let content;
+        parenthesized!(content in input);
+        loop {
+            if content.is_empty() {
+                break;
+            }
+
+            let id = content.parse::<Ident>()?;
+
+            if id == "async_check" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                opts.async_checks.push(ts);
+            } else if id == "check_with" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                opts.checks_with.push(ts);
+            } else if id == "required" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                opts.required.push(ts);
+            } else if id == "forbidden" {
+                content.parse::<Token![=]>()?;
+                let s = content.parse::<LitStr>()?;
+                let ts: TokenStream = s.parse()?;
+                opts.forbidden.push(ts);
+            } else {
+                abort!(id, r#"unknown field option "{}""#, id);
+            }
+
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(opts)
+    }
+}
+
+/// The name of the field used in spans,
+/// Serde's rename attribute is also taken into account.
+fn field_name(field: &syn::Field) -> String {
+    for a in &field.attrs {
+        if !a.path.is_ident("serde") {
+            continue;
+        }
+
+        if let Ok(Meta::List(list)) = a.parse_meta() {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident("rename") {
+                        if let Lit::Str(s) = nv.lit {
+                            return s.value();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    field
+        .ident
+        .as_ref()
+        .map(|i| i.to_string())
+        .unwrap_or_default()
+}
+
+/// The checks of the struct fields with the member, the name that is used
+/// for the span, and the path of the check function.
+fn field_checks(
+    input: &syn::DeriveInput,
+    checks: impl Fn(FieldOptions) -> Vec<TokenStream>,
+) -> Vec<(TokenStream, String, TokenStream)> {
+    let fields = match &input.data {
+        Data::Struct(s) => &s.fields,
+        _ => return Vec::new(),
+    };
+
+    let mut all_checks = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let mut field_opts = FieldOptions::default();
+
+        for a in &field.attrs {
+            if a.path.is_ident("verify") {
+                match syn::parse2::<FieldOptions>(a.tokens.clone()) {
+                    Ok(opts) => {
+                        field_opts.async_checks.extend(opts.async_checks);
+                        field_opts.checks_with.extend(opts.checks_with);
+                        field_opts.required.extend(opts.required);
+                        field_opts.forbidden.extend(opts.forbidden);
+                    }
+                    Err(err) => abort!(err.span(), "{}", err),
+                }
+            }
+        }
+
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        };
+
+        let name = match fields {
+            Fields::Named(_) => field_name(field),
+            _ => i.to_string(),
+        };
+
+        for check in checks(field_opts) {
+            all_checks.push((member.clone(), name.clone(), check));
+        }
+    }
+
+    all_checks
+}
+
 struct Verify {
     input: syn::DeriveInput,
     options: VerifyOptions,
@@ -244,26 +541,301 @@ impl Verify {
                 );
             }
         }
+
+        if self.options.context.is_none() {
+            if let Some(c) = self.options.checks_with.first() {
+                abort!(c.0, r#"the "context" option is required for "check_with""#);
+            }
+        }
+
+        if let Some(p) = self.options.partial.first() {
+            if self.options.is_schemars.is_none() {
+                abort!(p.0, r#""partial" is only supported with Schemars"#);
+            }
+        }
+
+        match &self.options.group {
+            Some(g) => {
+                if self.options.context.is_some() {
+                    abort!(g.0, r#"groups are not supported with "context""#);
+                }
+            }
+            None => {
+                if let Some(p) = self.options.partial.first() {
+                    abort!(p.0, r#"the "group" option is required for "partial""#);
+                }
+
+                if let Some((c, _, _)) = self.options.checks.iter().find(|c| c.2.is_some()) {
+                    abort!(c, r#"the "group" option is required for group checks"#);
+                }
+
+                let mut field_groups = field_checks(&self.input, |opts| opts.required);
+                field_groups.extend(field_checks(&self.input, |opts| opts.forbidden));
+
+                if let Some((_, _, pat)) = field_groups.first() {
+                    abort!(pat, r#"the "group" option is required for group rules"#);
+                }
+            }
+        }
     }
 
     fn derive(self) -> TokenStream {
         self.check_options();
 
+        let (verifier_error, verify) = self.verify_expr(None);
+
+        let group_impl = match &self.options.group {
+            Some((_, group)) => {
+                let partial = if self.options.partial.is_empty() {
+                    None
+                } else {
+                    let patterns = self.options.partial.iter().map(|p| &p.1);
+                    Some(quote!(::core::matches!(group, #(#patterns)|*)))
+                };
+
+                let (_, verify) = self.verify_expr(partial);
+                self.implement_group(verifier_error.clone(), verify, group)
+            }
+            None => TokenStream::new(),
+        };
+
+        let verify_impl = self.implement(verifier_error, verify);
+        let async_impl = self.implement_async();
+
+        quote!(#verify_impl #group_impl #async_impl)
+    }
+
+    /// Implement AsyncVerify for structs with async field checks.
+    fn implement_async(&self) -> TokenStream {
+        let checks = field_checks(&self.input, |opts| opts.async_checks);
+
+        if checks.is_empty() {
+            return TokenStream::new();
+        }
+
+        if let Some(ctx) = &self.options.context {
+            abort!(ctx.0, r#"async checks are not supported with "context""#);
+        }
+
+        let span_ty = span_type(&self.options);
+
+        let ident = &self.input.ident;
+        let mut generics = self.generics();
+
+        // The future holds a reference to the value, so it must be shared between threads.
+        if !generics.params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(Self: ::core::marker::Sync));
+        }
+
+        let (impl_gen, ty_gen, where_gen) = generics.split_for_impl();
+
+        let checks = checks.iter().map(|(member, name, check)| {
+            quote! {
+                Box::pin(async move {
+                    #check(&self.#member).await.map_err(|e| {
+                        <Self::Error as ::verify::adhoc::SpannedError<#span_ty>>::spanned(
+                            Some(<#span_ty as ::core::convert::From<String>>::from(#name.to_string())),
+                            e.to_string(),
+                        )
+                    })
+                }) as ::verify::future::BoxFuture<'_, Result<(), Self::Error>>
+            }
+        });
+
+        quote! {
+            impl#impl_gen ::verify::future::AsyncVerify for #ident#ty_gen #where_gen {
+                type Error = <Self as ::verify::Verify>::Error;
+
+                fn verify_async(&self) -> ::verify::future::BoxFuture<'_, Result<(), Self::Error>> {
+                    Box::pin(async move {
+                        ::verify::Verify::verify(self)?;
+
+                        ::verify::future::all(vec![#(#checks),*]).await
+                    })
+                }
+            }
+        }
+    }
+
+    /// The generics of the type, with the bounds required by the verifier.
+    fn generics(&self) -> syn::Generics {
+        let mut generics = self.input.generics.clone();
+
+        // Generic types only implement these if the type parameters do.
+        if self.options.is_schemars.is_some() && !generics.params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(Self: ::serde::Serialize + schemars::JsonSchema));
+        }
+
+        generics
+    }
+
+    /// The error type and the verification expression,
+    /// `partial` is an expression that decides whether `required` is ignored.
+    fn verify_expr(&self, partial: Option<TokenStream>) -> (TokenStream, TokenStream) {
         if self.options.is_serde.is_some() {
-            return self.derive_serde();
+            if self.options.is_schemars.is_some() {
+                self.derive_schemars(partial)
+            } else {
+                self.derive_serde()
+            }
+        } else {
+            self.derive_plain()
         }
+    }
+
+    /// Implement Verify, or VerifyWith if there is a context,
+    /// the verification is followed by the custom checks.
+    fn implement(&self, verifier_error: TokenStream, verify: TokenStream) -> TokenStream {
+        let ident = &self.input.ident;
+        let generics = self.generics();
+        let (impl_gen, ty_gen, where_gen) = generics.split_for_impl();
 
-        let ident = self.input.ident;
-        let (impl_gen, ty_gen, where_gen) = self.input.generics.split_for_impl();
+        let span_ty = span_type(&self.options);
 
+        let mut checks: Vec<TokenStream> = Vec::new();
+
+        for (member, name, check) in field_checks(&self.input, |opts| opts.checks_with) {
+            checks.push(quote! {
+                #check(&self.#member, ctx).map_err(|e| {
+                    <Self::Error as ::verify::adhoc::SpannedError<#span_ty>>::spanned(
+                        Some(<#span_ty as ::core::convert::From<String>>::from(#name.to_string())),
+                        e.to_string(),
+                    )
+                })
+            });
+        }
+
+        for (_, check, _) in self.options.checks.iter().filter(|c| c.2.is_none()) {
+            checks.push(quote! {
+                #check(self)
+            });
+        }
+
+        for (_, check) in &self.options.checks_with {
+            checks.push(quote! {
+                #check(self, ctx)
+            });
+        }
+
+        let body = merge_checks(verify, checks);
+
+        match &self.options.context {
+            Some((_, ctx)) => quote! {
+                impl#impl_gen ::verify::VerifyWith<#ctx> for #ident#ty_gen #where_gen {
+                    type Error = #verifier_error;
+
+                    fn verify_with(&self, ctx: &#ctx) -> Result<(), Self::Error> {
+                        #body
+                    }
+                }
+            },
+            None => quote! {
+                impl#impl_gen ::verify::Verify for #ident#ty_gen #where_gen {
+                    type Error = #verifier_error;
+
+                    fn verify(&self) -> Result<(), Self::Error> {
+                        #body
+                    }
+                }
+            },
+        }
+    }
+
+    /// Implement VerifyGroup, the checks without a group
+    /// are followed by the rules of the group.
+    fn implement_group(
+        &self,
+        verifier_error: TokenStream,
+        verify: TokenStream,
+        group: &TokenStream,
+    ) -> TokenStream {
+        let ident = &self.input.ident;
+        let generics = self.generics();
+        let (impl_gen, ty_gen, where_gen) = generics.split_for_impl();
+
+        let span_ty = span_type(&self.options);
+
+        let mut checks: Vec<TokenStream> = Vec::new();
+
+        let spanned = |name: &str, constructor: TokenStream| {
+            quote! {
+                Err(<Self::Error as ::verify::adhoc::SpannedError<#span_ty>>::#constructor(
+                    Some(<#span_ty as ::core::convert::From<String>>::from(#name.to_string())),
+                    #name,
+                ))
+            }
+        };
+
+        for (member, name, pattern) in field_checks(&self.input, |opts| opts.required) {
+            let err = spanned(&name, quote!(missing_property));
+
+            checks.push(quote! {
+                if ::core::matches!(group, #pattern) && self.#member.is_none() {
+                    #err
+                } else {
+                    Ok(())
+                }
+            });
+        }
+
+        for (member, name, pattern) in field_checks(&self.input, |opts| opts.forbidden) {
+            let err = spanned(&name, quote!(forbidden_property));
+
+            checks.push(quote! {
+                if ::core::matches!(group, #pattern) && self.#member.is_some() {
+                    #err
+                } else {
+                    Ok(())
+                }
+            });
+        }
+
+        for (_, check, pattern) in &self.options.checks {
+            checks.push(match pattern {
+                Some(pattern) => quote! {
+                    if ::core::matches!(group, #pattern) {
+                        #check(self)
+                    } else {
+                        Ok(())
+                    }
+                },
+                None => quote! {
+                    #check(self)
+                },
+            });
+        }
+
+        let body = merge_checks(verify, checks);
+
+        quote! {
+            impl#impl_gen ::verify::VerifyGroup<#group> for #ident#ty_gen #where_gen {
+                type Error = #verifier_error;
+
+                #[allow(unused_variables)]
+                fn verify_group(&self, group: #group) -> Result<(), Self::Error> {
+                    #body
+                }
+            }
+        }
+    }
+
+    fn derive_plain(&self) -> (TokenStream, TokenStream) {
         let verifier_name = self
             .options
             .verifier_name
+            .as_ref()
             .unwrap_or_else(|| abort_call_site!("verifier name is required"))
-            .1;
+            .1
+            .clone();
 
-        let verifier_error = match self.options.verifier_error {
-            Some(e) => e.1,
+        let verifier_error = match &self.options.verifier_error {
+            Some(e) => e.1.clone(),
             None => {
                 quote! {
                     <#verifier_name as ::verify::Verifier<<Self as ::verify::span::Spanned>::Span>>::Error
@@ -271,8 +843,8 @@ impl Verify {
             }
         };
 
-        let verifier_create = match self.options.verifier_create {
-            Some(e) => e.1,
+        let verifier_create = match &self.options.verifier_create {
+            Some(e) => e.1.clone(),
             None => {
                 quote! {
                     #verifier_name::default()
@@ -280,44 +852,32 @@ impl Verify {
             }
         };
 
-        quote! {
-            impl#impl_gen ::verify::Verify for #ident#ty_gen #where_gen {
-                type Error = #verifier_error;
-
-                fn verify(&self) -> Result<(), Self::Error> {
-                    let __v = #verifier_create;
-                    <#verifier_name as ::verify::Verifier<<Self as ::verify::span::Spanned>::Span>>::verify_value(
-                        &__v,
-                        self,
-                    )
-                }
+        let verify = quote! {
+            {
+                let __v = #verifier_create;
+                <#verifier_name as ::verify::Verifier<<Self as ::verify::span::Spanned>::Span>>::verify_value(
+                    &__v,
+                    self,
+                )
             }
-        }
-    }
+        };
 
-    fn derive_serde(self) -> TokenStream {
-        if self.options.is_schemars.is_some() {
-            return self.derive_schemars();
-        }
+        (verifier_error, verify)
+    }
 
-        let ident = self.input.ident;
-        let (impl_gen, ty_gen, where_gen) = self.input.generics.split_for_impl();
-
-        let spans = match self.options.serde_spans {
-            Some((_, s)) => s,
-            None => {
-                quote! {::verify::serde::KeySpans}
-            }
-        };
+    fn derive_serde(&self) -> (TokenStream, TokenStream) {
+        let spans = serde_spans(&self.options);
 
         let verifier_name = self
             .options
             .verifier_name
+            .as_ref()
             .unwrap_or_else(|| abort_call_site!("verifier name is required"))
-            .1;
+            .1
+            .clone();
 
-        let verifier_error = match self.options.verifier_error {
-            Some(e) => e.1,
+        let verifier_error = match &self.options.verifier_error {
+            Some(e) => e.1.clone(),
             None => {
                 quote! {
                     <#verifier_name as ::verify::Verifier<<#spans as ::verify::serde::Spans>::Span>>::Error
@@ -325,8 +885,8 @@ impl Verify {
             }
         };
 
-        let verifier_create = match self.options.verifier_create {
-            Some(e) => e.1,
+        let verifier_create = match &self.options.verifier_create {
+            Some(e) => e.1.clone(),
             None => {
                 quote! {
                     #verifier_name::default()
@@ -334,37 +894,23 @@ impl Verify {
             }
         };
 
-        quote! {
-            impl#impl_gen ::verify::Verify for #ident#ty_gen #where_gen {
-                type Error = #verifier_error;
-
-                fn verify(&self) -> Result<(), Self::Error> {
-                    let __v = #verifier_create;
-                    <#verifier_name as ::verify::Verifier<<#spans as ::verify::serde::Spans>::Span>>::verify_value(
-                        &__v,
-                        &::verify::serde::Spanned::new(self, #spans::default()),
-                    )
-                }
-            }
-        }
-    }
-
-    fn derive_schemars(self) -> TokenStream {
-        let ident = self.input.ident;
-        let (impl_gen, ty_gen, where_gen) = self.input.generics.split_for_impl();
-
-        let spans = match self.options.serde_spans {
-            Some((_, s)) => s,
-            None => {
-                quote! {::verify::serde::KeySpans}
+        let verify = quote! {
+            {
+                let __v = #verifier_create;
+                <#verifier_name as ::verify::Verifier<<#spans as ::verify::serde::Spans>::Span>>::verify_value(
+                    &__v,
+                    &::verify::serde::Spanned::new(self, #spans::default()),
+                )
             }
         };
 
-        if let Some(v) = self.options.verifier {
-            abort!(v, "verifier option is not supported with Schemars");
-        }
+        (verifier_error, verify)
+    }
 
-        if let Some(v) = self.options.verifier {
+    fn derive_schemars(&self, partial: Option<TokenStream>) -> (TokenStream, TokenStream) {
+        let spans = serde_spans(&self.options);
+
+        if let Some(v) = &self.options.verifier {
             abort!(v, "verifier option is not supported with Schemars");
         }
 
@@ -372,19 +918,94 @@ impl Verify {
             ::verify::schemars::errors::Errors<<#spans as ::verify::serde::Spans>::Span>
         };
 
-        quote! {
-            impl#impl_gen ::verify::Verify for #ident#ty_gen #where_gen {
-                type Error = #verifier_error;
+        // Schemas are cached by type ids, which requires 'static types,
+        // generic types might not be, so their schemas are generated every time.
+        let is_generic = !self.input.generics.params.is_empty();
+
+        let root = match &self.options.schemars_settings {
+            Some((_, settings)) if is_generic => quote! {
+                ::std::sync::Arc::new(
+                    schemars::gen::SchemaGenerator::new((#settings)()).into_root_schema_for::<Self>(),
+                )
+            },
+            None if is_generic => quote! {
+                ::std::sync::Arc::new(schemars::schema_for!(Self))
+            },
+            Some((_, settings)) => quote! {
+                ::verify::schemars::cached_schema_with::<Self, _>(#settings)
+            },
+            None => quote! {
+                ::verify::schemars::cached_schema::<Self>()
+            },
+        };
 
-                fn verify(&self) -> Result<(), Self::Error> {
-                    let __root = schemars::schema_for!(Self);
+        let verify = match partial {
+            None => quote! {
+                {
+                    let __root = #root;
 
                     <schemars::schema::RootSchema as ::verify::Verifier<_>>::verify_value(
-                        &__root,
+                        &*__root,
+                        &::verify::serde::Spanned::new(self, #spans::default()),
+                    )
+                }
+            },
+            Some(partial) => quote! {
+                {
+                    let __root = #root;
+
+                    <::verify::schemars::SchemaVerifier as ::verify::Verifier<_>>::verify_value(
+                        &::verify::schemars::SchemaVerifier::new(&__root).partial(#partial),
                         &::verify::serde::Spanned::new(self, #spans::default()),
                     )
                 }
+            },
+        };
+
+        (verifier_error, verify)
+    }
+}
+
+/// Run the checks after the verification, and merge all the errors.
+fn merge_checks(verify: TokenStream, checks: Vec<TokenStream>) -> TokenStream {
+    if checks.is_empty() {
+        return verify;
+    }
+
+    quote! {
+        let mut __errors: Option<Self::Error> = (#verify).err();
+
+        #(
+            if let Err(__e) = #checks {
+                match &mut __errors {
+                    Some(__errs) => *__errs += __e,
+                    None => __errors = Some(__e),
+                }
             }
+        )*
+
+        match __errors {
+            None => Ok(()),
+            Some(__e) => Err(__e),
         }
     }
 }
+
+fn serde_spans(options: &VerifyOptions) -> TokenStream {
+    match &options.serde_spans {
+        Some((_, s)) => s.clone(),
+        None => {
+            quote! {::verify::serde::KeySpans}
+        }
+    }
+}
+
+/// The span type of the errors.
+fn span_type(options: &VerifyOptions) -> TokenStream {
+    if options.is_serde.is_some() {
+        let spans = serde_spans(options);
+        quote! {<#spans as ::verify::serde::Spans>::Span}
+    } else {
+        quote! {<Self as ::verify::span::Spanned>::Span}
+    }
+}
@@ -0,0 +1,771 @@
+//! Validators built from closures.
+//!
+//! Implementing [Validator](crate::Validator) is a lot of work for simple checks,
+//! [AdHoc](AdHoc) creates one from closures instead. Every value without a closure
+//! is accepted, and nested values are validated recursively.
+//!
+//! Spans are tracked automatically, the closures only return an error message:
+//!
+//! ```ignore
+//! let verifier: AdHoc<Keys> = AdHoc::new()
+//!     .on_str(|s| {
+//!         if s.trim().is_empty() {
+//!             return Err("blank strings are not allowed".into());
+//!         }
+//!         Ok(())
+//!     })
+//!     .on_map_entry(|key, value| match (key, value) {
+//!         ("port", Peek::U64(0)) => Err("the port must not be 0".into()),
+//!         _ => Ok(()),
+//!     });
+//!
+//! verifier.verify_value(&Spanned::new(&config, KeySpans::default()))?;
+//! ```
+
+use crate::{
+    span::{Span, SpanExt},
+    Error, Validate, ValidateMap, ValidateSeq, Validator, Verifier,
+};
+use std::{convert::TryFrom, marker::PhantomData, ops::AddAssign};
+
+pub use crate::SpannedError;
+
+/// An error returned by a closure of [AdHoc](AdHoc).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<S: Span> {
+    /// The span of the invalid value.
+    pub span: Option<S>,
+
+    /// The message returned by the closure.
+    pub message: String,
+}
+
+impl<S: Span> core::fmt::Display for Failure<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            if let Some(span) = &self.span {
+                write!(f, "({:?}) ", span)?;
+            }
+        }
+
+        f.write_str(&self.message)
+    }
+}
+
+/// A collection of [Failures](Failure), this is the default error of [AdHoc](AdHoc).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failures<S: Span>(pub Vec<Failure<S>>);
+
+impl<S: Span> core::fmt::Display for Failures<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for e in &self.0 {
+            writeln!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Span> std::error::Error for Failures<S> {}
+
+impl<S: Span> AddAssign for Failures<S> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.extend(rhs.0)
+    }
+}
+
+impl<S: Span> Error for Failures<S> {
+    fn custom<T: core::fmt::Display>(error: T) -> Self {
+        Self::spanned(None, error.to_string())
+    }
+}
+
+impl<S: Span> SpannedError<S> for Failures<S> {
+    fn spanned(span: Option<S>, message: String) -> Self {
+        Failures(vec![Failure { span, message }])
+    }
+}
+
+/// A shallow view of a map value that is passed to
+/// [on_map_entry](AdHoc::on_map_entry).
+///
+/// Sequences and maps are not collected, their items
+/// are validated recursively instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Peek {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    /// A [None](Option::None) or a unit value.
+    None,
+    Seq,
+    Map,
+}
+
+type Handler<T> = Option<Box<dyn Fn(T) -> Result<(), String> + Send + Sync>>;
+type StrHandler = Option<Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>>;
+type BytesHandler = Option<Box<dyn Fn(&[u8]) -> Result<(), String> + Send + Sync>>;
+type EntryHandler = Option<Box<dyn Fn(&str, &Peek) -> Result<(), String> + Send + Sync>>;
+
+/// A [Verifier](crate::Verifier) that is built from closures.
+///
+/// Read more [here](self).
+pub struct AdHoc<S: Span, E: SpannedError<S> = Failures<S>> {
+    on_bool: Handler<bool>,
+    on_i64: Handler<i64>,
+    on_u64: Handler<u64>,
+    on_f64: Handler<f64>,
+    on_str: StrHandler,
+    on_bytes: BytesHandler,
+    on_none: Handler<()>,
+    on_seq: Handler<usize>,
+    on_map: Handler<usize>,
+    on_map_entry: EntryHandler,
+
+    _marker: PhantomData<fn() -> (S, E)>,
+}
+
+impl<S: Span, E: SpannedError<S>> Default for AdHoc<S, E> {
+    fn default() -> Self {
+        Self {
+            on_bool: None,
+            on_i64: None,
+            on_u64: None,
+            on_f64: None,
+            on_str: None,
+            on_bytes: None,
+            on_none: None,
+            on_seq: None,
+            on_map: None,
+            on_map_entry: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Span, E: SpannedError<S>> AdHoc<S, E> {
+    /// Create a verifier that accepts everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check bool values.
+    pub fn on_bool<F>(mut self, f: F) -> Self
+    where
+        F: Fn(bool) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_bool = Some(Box::new(f));
+        self
+    }
+
+    /// Check signed integers, they are all converted to i64.
+    ///
+    /// i128 values that don't fit are checked with [on_f64](AdHoc::on_f64) instead.
+    pub fn on_i64<F>(mut self, f: F) -> Self
+    where
+        F: Fn(i64) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_i64 = Some(Box::new(f));
+        self
+    }
+
+    /// Check unsigned integers, they are all converted to u64.
+    ///
+    /// u128 values that don't fit are checked with [on_f64](AdHoc::on_f64) instead.
+    pub fn on_u64<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_u64 = Some(Box::new(f));
+        self
+    }
+
+    /// Check floating point numbers.
+    pub fn on_f64<F>(mut self, f: F) -> Self
+    where
+        F: Fn(f64) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_f64 = Some(Box::new(f));
+        self
+    }
+
+    /// Check strings, chars and unit enum variants.
+    pub fn on_str<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_str = Some(Box::new(f));
+        self
+    }
+
+    /// Check byte slices.
+    pub fn on_bytes<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&[u8]) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_bytes = Some(Box::new(f));
+        self
+    }
+
+    /// Check [None](Option::None) and unit values.
+    pub fn on_none<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_none = Some(Box::new(move |_| f()));
+        self
+    }
+
+    /// Check the length of sequences after all the items were validated.
+    pub fn on_seq<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_seq = Some(Box::new(f));
+        self
+    }
+
+    /// Check the number of entries in maps after all of them were validated.
+    pub fn on_map<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_map = Some(Box::new(f));
+        self
+    }
+
+    /// Check map entries with string keys.
+    ///
+    /// The value is also validated recursively by the other closures.
+    pub fn on_map_entry<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, &Peek) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.on_map_entry = Some(Box::new(f));
+        self
+    }
+}
+
+impl<S: Span, E: SpannedError<S>> Verifier<S> for AdHoc<S, E> {
+    type Error = E;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        value.validate(AdHocValidator::new(self).with_spans(span, value.span()))
+    }
+}
+
+/// The validator that calls the closures of [AdHoc](AdHoc).
+///
+/// It is also used for sequences and maps.
+struct AdHocValidator<'h, S: Span, E: SpannedError<S>> {
+    handlers: &'h AdHoc<S, E>,
+
+    parent_span: Option<S>,
+    span: Option<S>,
+    combined_span: Option<S>,
+
+    count: usize,
+    last_key: Option<String>,
+}
+
+impl<'h, S: Span, E: SpannedError<S>> AdHocValidator<'h, S, E> {
+    fn new(handlers: &'h AdHoc<S, E>) -> Self {
+        Self {
+            handlers,
+            parent_span: None,
+            span: None,
+            combined_span: None,
+            count: 0,
+            last_key: None,
+        }
+    }
+
+    fn with_spans(mut self, parent: Option<S>, span: Option<S>) -> Self {
+        self.parent_span = parent;
+        self.span = span;
+        self.combine_spans();
+        self
+    }
+
+    fn combine_spans(&mut self) {
+        self.combined_span = self.parent_span.clone();
+        if self.span.is_some() {
+            self.combined_span.combine(self.span.clone());
+        }
+    }
+
+    fn set_span(&mut self, span: Option<S>) {
+        match span {
+            Some(s) => {
+                self.span = Some(s);
+            }
+            None => {
+                self.parent_span = None;
+                self.span = None;
+            }
+        }
+        self.combine_spans();
+    }
+
+    fn check<T>(&self, handler: &Handler<T>, v: T) -> Result<(), E> {
+        match handler {
+            Some(f) => f(v).map_err(|message| E::spanned(self.combined_span.clone(), message)),
+            None => Ok(()),
+        }
+    }
+
+    fn check_str(&self, v: &str) -> Result<(), E> {
+        match &self.handlers.on_str {
+            Some(f) => f(v).map_err(|message| E::spanned(self.combined_span.clone(), message)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'h, S: Span, E: SpannedError<S>> Validator<S> for AdHocValidator<'h, S, E> {
+    type Error = E;
+
+    type ValidateSeq = Self;
+    type ValidateMap = Self;
+
+    fn with_span(mut self, span: Option<S>) -> Self {
+        self.set_span(span);
+        self
+    }
+
+    fn validate_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_bool, v)
+    }
+
+    fn validate_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.validate_i64(v as i64)
+    }
+
+    fn validate_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.validate_i64(v as i64)
+    }
+
+    fn validate_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.validate_i64(v as i64)
+    }
+
+    fn validate_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_i64, v)
+    }
+
+    fn validate_i128(self, v: i128) -> Result<(), Self::Error> {
+        match i64::try_from(v) {
+            Ok(v) => self.validate_i64(v),
+            Err(_) => self.validate_f64(v as f64),
+        }
+    }
+
+    fn validate_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.validate_u64(v as u64)
+    }
+
+    fn validate_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.validate_u64(v as u64)
+    }
+
+    fn validate_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.validate_u64(v as u64)
+    }
+
+    fn validate_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_u64, v)
+    }
+
+    fn validate_u128(self, v: u128) -> Result<(), Self::Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.validate_u64(v),
+            Err(_) => self.validate_f64(v as f64),
+        }
+    }
+
+    fn validate_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.validate_f64(v as f64)
+    }
+
+    fn validate_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_f64, v)
+    }
+
+    fn validate_char(self, v: char) -> Result<(), Self::Error> {
+        self.check_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn validate_str(self, v: &str) -> Result<(), Self::Error> {
+        self.check_str(v)
+    }
+
+    fn validate_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        match &self.handlers.on_bytes {
+            Some(f) => f(v).map_err(|message| E::spanned(self.combined_span.clone(), message)),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_none(self) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_none, ())
+    }
+
+    fn validate_some<V>(self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        value.validate(self)
+    }
+
+    fn validate_unit(self) -> Result<(), Self::Error> {
+        self.validate_none()
+    }
+
+    fn validate_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        self.validate_none()
+    }
+
+    fn validate_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.validate_str(variant)
+    }
+
+    fn validate_seq(mut self, _len: Option<usize>) -> Result<Self::ValidateSeq, Self::Error> {
+        self.parent_span = self.combined_span.clone();
+        Ok(self)
+    }
+
+    fn validate_map(mut self, _len: Option<usize>) -> Result<Self::ValidateMap, Self::Error> {
+        self.parent_span = self.combined_span.clone();
+        Ok(self)
+    }
+
+    fn validate_tag<V>(&mut self, _tag: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        Ok(())
+    }
+}
+
+impl<'h, S: Span, E: SpannedError<S>> ValidateSeq<S> for AdHocValidator<'h, S, E> {
+    type Error = E;
+
+    fn with_span(&mut self, span: Option<S>) -> &mut Self {
+        self.set_span(span);
+        self
+    }
+
+    fn validate_element<V>(&mut self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + core::hash::Hash,
+    {
+        self.count += 1;
+
+        value.validate(
+            AdHocValidator::new(self.handlers).with_spans(self.parent_span.clone(), value.span()),
+        )
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_seq, self.count)
+    }
+}
+
+impl<'h, S: Span, E: SpannedError<S>> ValidateMap<S> for AdHocValidator<'h, S, E> {
+    type Error = E;
+
+    fn with_span(&mut self, span: Option<S>) -> &mut Self {
+        self.set_span(span);
+        self
+    }
+
+    fn validate_key<V>(&mut self, key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        self.count += 1;
+        self.last_key = None;
+
+        key.validate(
+            AdHocValidator::new(self.handlers).with_spans(self.parent_span.clone(), key.span()),
+        )
+    }
+
+    fn validate_string_key<V>(&mut self, key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        self.count += 1;
+        self.last_key = Some(key.to_string());
+        Ok(())
+    }
+
+    fn validate_value<V>(&mut self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        let mut error = None;
+
+        if let (Some(f), Some(key)) = (&self.handlers.on_map_entry, self.last_key.take()) {
+            let peek = peek::<S, E, V>(value)?;
+
+            if let Err(message) = f(&key, &peek) {
+                error = Some(E::spanned(self.parent_span.combined(value.span()), message));
+            }
+        }
+
+        if let Err(e) = value.validate(
+            AdHocValidator::new(self.handlers).with_spans(self.parent_span.clone(), value.span()),
+        ) {
+            match &mut error {
+                Some(err) => *err += e,
+                None => error = Some(e),
+            }
+        }
+
+        match error {
+            None => Ok(()),
+            Some(e) => Err(e),
+        }
+    }
+
+    fn string_key_required(&self) -> bool {
+        true
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.check(&self.handlers.on_map, self.count)
+    }
+}
+
+/// Peek at the shallow value without validating it.
+pub(crate) fn peek<S, E, V>(value: &V) -> Result<Peek, E>
+where
+    S: Span,
+    E: SpannedError<S>,
+    V: ?Sized + Validate<Span = S>,
+{
+    let mut peek = Peek::None;
+    value.validate(PeekValidator::<S, E>::new(&mut peek))?;
+    Ok(peek)
+}
+
+/// A validator that only records the shallow value for [Peek](Peek).
+struct PeekValidator<'p, S, E> {
+    out: &'p mut Peek,
+    _marker: PhantomData<fn() -> (S, E)>,
+}
+
+impl<'p, S: Span, E: SpannedError<S>> PeekValidator<'p, S, E> {
+    fn new(out: &'p mut Peek) -> Self {
+        Self {
+            out,
+            _marker: PhantomData,
+        }
+    }
+
+    fn set(self, value: Peek) -> Result<(), E> {
+        *self.out = value;
+        Ok(())
+    }
+}
+
+impl<'p, S: Span, E: SpannedError<S>> Validator<S> for PeekValidator<'p, S, E> {
+    type Error = E;
+
+    type ValidateSeq = Skip<S, E>;
+    type ValidateMap = Skip<S, E>;
+
+    fn with_span(self, _span: Option<S>) -> Self {
+        self
+    }
+
+    fn validate_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.set(Peek::Bool(v))
+    }
+
+    fn validate_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.set(Peek::I64(v as i64))
+    }
+
+    fn validate_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.set(Peek::I64(v as i64))
+    }
+
+    fn validate_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.set(Peek::I64(v as i64))
+    }
+
+    fn validate_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.set(Peek::I64(v))
+    }
+
+    fn validate_i128(self, v: i128) -> Result<(), Self::Error> {
+        match i64::try_from(v) {
+            Ok(v) => self.set(Peek::I64(v)),
+            Err(_) => self.set(Peek::F64(v as f64)),
+        }
+    }
+
+    fn validate_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.set(Peek::U64(v as u64))
+    }
+
+    fn validate_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.set(Peek::U64(v as u64))
+    }
+
+    fn validate_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.set(Peek::U64(v as u64))
+    }
+
+    fn validate_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.set(Peek::U64(v))
+    }
+
+    fn validate_u128(self, v: u128) -> Result<(), Self::Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.set(Peek::U64(v)),
+            Err(_) => self.set(Peek::F64(v as f64)),
+        }
+    }
+
+    fn validate_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.set(Peek::F64(v as f64))
+    }
+
+    fn validate_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.set(Peek::F64(v))
+    }
+
+    fn validate_char(self, v: char) -> Result<(), Self::Error> {
+        self.set(Peek::Str(v.to_string()))
+    }
+
+    fn validate_str(self, v: &str) -> Result<(), Self::Error> {
+        self.set(Peek::Str(v.to_string()))
+    }
+
+    fn validate_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.set(Peek::Bytes(v.to_vec()))
+    }
+
+    fn validate_none(self) -> Result<(), Self::Error> {
+        self.set(Peek::None)
+    }
+
+    fn validate_some<V>(self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        value.validate(self)
+    }
+
+    fn validate_unit(self) -> Result<(), Self::Error> {
+        self.set(Peek::None)
+    }
+
+    fn validate_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        self.set(Peek::None)
+    }
+
+    fn validate_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.set(Peek::Str(variant.to_string()))
+    }
+
+    fn validate_seq(self, _len: Option<usize>) -> Result<Self::ValidateSeq, Self::Error> {
+        self.set(Peek::Seq)?;
+        Ok(Skip(PhantomData))
+    }
+
+    fn validate_map(self, _len: Option<usize>) -> Result<Self::ValidateMap, Self::Error> {
+        self.set(Peek::Map)?;
+        Ok(Skip(PhantomData))
+    }
+
+    fn validate_tag<V>(&mut self, _tag: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        Ok(())
+    }
+}
+
+/// Ignores the contents of sequences and maps.
+struct Skip<S, E>(PhantomData<fn() -> (S, E)>);
+
+impl<S: Span, E: SpannedError<S>> ValidateSeq<S> for Skip<S, E> {
+    type Error = E;
+
+    fn with_span(&mut self, _span: Option<S>) -> &mut Self {
+        self
+    }
+
+    fn validate_element<V>(&mut self, _value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + core::hash::Hash,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<S: Span, E: SpannedError<S>> ValidateMap<S> for Skip<S, E> {
+    type Error = E;
+
+    fn with_span(&mut self, _span: Option<S>) -> &mut Self {
+        self
+    }
+
+    fn validate_key<V>(&mut self, _key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        Ok(())
+    }
+
+    fn validate_string_key<V>(&mut self, _key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        Ok(())
+    }
+
+    fn validate_value<V>(&mut self, _value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        Ok(())
+    }
+
+    fn string_key_required(&self) -> bool {
+        false
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+//! Combinators for [Verifiers](crate::Verifier).
+//!
+//! They make it possible to layer verifiers on top of each other,
+//! for example a business rule verifier on top of a schema:
+//!
+//! ```ignore
+//! use verify::combinators::VerifierExt;
+//!
+//! let verifier = (&schema).and(NoReservedNames).map_err(ApiError::from);
+//!
+//! verifier.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//! ```
+//!
+//! All the combinators are also available through [VerifierExt](VerifierExt).
+
+use crate::{
+    span::{Span, SpanExt},
+    Error, SpannedError, Validate, Verifier,
+};
+
+/// The value must be valid for both verifiers.
+///
+/// Both verifiers always run, and their errors are merged
+/// with [AddAssign](core::ops::AddAssign).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Both<A, B>(pub A, pub B);
+
+impl<S, A, B> Verifier<S> for Both<A, B>
+where
+    S: Span,
+    A: Verifier<S>,
+    B: Verifier<S, Error = A::Error>,
+{
+    type Error = A::Error;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        merge(self.0.verify_value(value), self.1.verify_value(value))
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        merge(
+            self.0.verify_value_with_span(value, span.clone()),
+            self.1.verify_value_with_span(value, span),
+        )
+    }
+}
+
+/// The value must be valid for at least one of the verifiers.
+///
+/// The second verifier only runs if the first one fails,
+/// if both of them fail, their errors are merged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Either<A, B>(pub A, pub B);
+
+impl<S, A, B> Verifier<S> for Either<A, B>
+where
+    S: Span,
+    A: Verifier<S>,
+    B: Verifier<S, Error = A::Error>,
+{
+    type Error = A::Error;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        match self.0.verify_value_with_span(value, span.clone()) {
+            Ok(_) => Ok(()),
+            Err(mut a) => match self.1.verify_value_with_span(value, span) {
+                Ok(_) => Ok(()),
+                Err(b) => {
+                    a += b;
+                    Err(a)
+                }
+            },
+        }
+    }
+}
+
+/// The value must not be valid for the verifier.
+///
+/// The errors of the inner verifier are discarded,
+/// a [spanned](crate::SpannedError) error is returned if the value is valid.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Not<V>(pub V);
+
+impl<S, N> Verifier<S> for Not<N>
+where
+    S: Span,
+    N: Verifier<S>,
+    N::Error: SpannedError<S>,
+{
+    type Error = N::Error;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        match self.0.verify_value_with_span(value, span.clone()) {
+            Ok(_) => {
+                let mut error_span = span;
+                if let Some(value_span) = value.span() {
+                    error_span.combine(Some(value_span));
+                }
+
+                Err(N::Error::spanned(
+                    error_span,
+                    "the value must not be valid".into(),
+                ))
+            }
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Maps the error of the verifier with a function.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MapErr<V, F>(pub V, pub F);
+
+impl<S, M, F, E> Verifier<S> for MapErr<M, F>
+where
+    S: Span,
+    M: Verifier<S>,
+    F: Fn(M::Error) -> E,
+    E: Error,
+{
+    type Error = E;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.0.verify_value(value).map_err(&self.1)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        self.0.verify_value_with_span(value, span).map_err(&self.1)
+    }
+}
+
+/// Convenience methods for combining verifiers.
+///
+/// It is implemented for every type, so that the span type of verifiers
+/// that support any span (such as schemas) is inferred when the combined
+/// verifier is used. The combinators are only verifiers if the combined
+/// verifiers are, and their errors match.
+pub trait VerifierExt: Sized {
+    /// Combine with another verifier with [Both](Both).
+    fn and<B>(self, other: B) -> Both<Self, B> {
+        Both(self, other)
+    }
+
+    /// Combine with another verifier with [Either](Either).
+    fn or<B>(self, other: B) -> Either<Self, B> {
+        Either(self, other)
+    }
+
+    /// Negate the verifier with [Not](Not).
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+
+    /// Map the errors of the verifier with [MapErr](MapErr).
+    fn map_err<F>(self, f: F) -> MapErr<Self, F> {
+        MapErr(self, f)
+    }
+}
+
+impl<T> VerifierExt for T {}
+
+fn merge<E: Error>(a: Result<(), E>, b: Result<(), E>) -> Result<(), E> {
+    match (a, b) {
+        (Ok(_), Ok(_)) => Ok(()),
+        (Err(e), Ok(_)) | (Ok(_), Err(e)) => Err(e),
+        (Err(mut a), Err(b)) => {
+            a += b;
+            Err(a)
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Asynchronous verification for checks that need I/O.
+//!
+//! Some rules cannot be checked without a lookup, for example whether a username
+//! is already taken. [AsyncVerify](AsyncVerify) and [AsyncVerifier](AsyncVerifier)
+//! are the asynchronous counterparts of [Verify](crate::Verify) and [Verifier](crate::Verifier).
+//!
+//! The derive macro implements [AsyncVerify](AsyncVerify) for structs that have fields with
+//! the `async_check` attribute. The synchronous validation runs first, then the
+//! checks of all the fields run concurrently:
+//!
+//! ```ignore
+//! #[derive(Verify, Serialize, JsonSchema)]
+//! #[verify(schemars, serde)]
+//! struct SignUp {
+//!     #[verify(async_check = "username_available")]
+//!     username: String,
+//! }
+//!
+//! async fn username_available(username: &String) -> Result<(), String> {
+//!     // ...
+//! }
+//!
+//! sign_up.verify_async().await?;
+//! ```
+//!
+//! No executor is required, the futures work with any runtime.
+
+use crate::{span::Span, Error, Validate, Verifier};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A boxed future that is returned by the asynchronous traits.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// This trait is implemented by types that validate a value asynchronously.
+pub trait AsyncVerifier<S: Span> {
+    /// The error returned by the validator.
+    type Error: Error;
+
+    /// Validate a value asynchronously.
+    fn verify_value_async<'a, V>(&'a self, value: &'a V) -> BoxFuture<'a, Result<(), Self::Error>>
+    where
+        V: ?Sized + Validate<Span = S> + Sync;
+}
+
+/// Use a synchronous [Verifier](crate::Verifier) where an [AsyncVerifier](AsyncVerifier) is expected.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ready<V>(pub V);
+
+impl<S, R> AsyncVerifier<S> for Ready<R>
+where
+    S: Span,
+    R: Verifier<S> + Sync,
+    R::Error: Send + 'static,
+{
+    type Error = R::Error;
+
+    fn verify_value_async<'a, V>(&'a self, value: &'a V) -> BoxFuture<'a, Result<(), Self::Error>>
+    where
+        V: ?Sized + Validate<Span = S> + Sync,
+    {
+        let result = self.0.verify_value(value);
+        Box::pin(async move { result })
+    }
+}
+
+/// This trait is implemented by types that can validate themselves asynchronously.
+pub trait AsyncVerify {
+    /// The error returned by the validator.
+    type Error: Error;
+
+    /// Validate self asynchronously.
+    fn verify_async(&self) -> BoxFuture<'_, Result<(), Self::Error>>;
+}
+
+/// Run all the checks concurrently, and merge their errors
+/// in the order of the checks.
+pub fn all<E: Error>(checks: Vec<BoxFuture<'_, Result<(), E>>>) -> All<'_, E> {
+    All {
+        checks: checks.into_iter().map(Check::Pending).collect(),
+    }
+}
+
+/// The future returned by [all](all).
+pub struct All<'a, E> {
+    checks: Vec<Check<'a, E>>,
+}
+
+enum Check<'a, E> {
+    Pending(BoxFuture<'a, Result<(), E>>),
+    Done(Result<(), E>),
+}
+
+// The results are never pinned.
+impl<'a, E> Unpin for All<'a, E> {}
+
+impl<'a, E: Error> Future for All<'a, E> {
+    type Output = Result<(), E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut done = true;
+
+        for check in self.checks.iter_mut() {
+            if let Check::Pending(future) = check {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(result) => *check = Check::Done(result),
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+
+        if !done {
+            return Poll::Pending;
+        }
+
+        // The errors are merged once all the checks are done,
+        // so their order does not depend on which check finished first.
+        let mut error: Option<E> = None;
+
+        for check in self.checks.drain(..) {
+            if let Check::Done(Err(e)) = check {
+                match &mut error {
+                    Some(err) => *err += e,
+                    None => error = Some(e),
+                }
+            }
+        }
+
+        match error {
+            None => Poll::Ready(Ok(())),
+            Some(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Cached schemas of types and compiled patterns.
+
+use regex::Regex;
+use schemars_crate::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::RootSchema,
+    JsonSchema,
+};
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+// The schemas are identified by the type and the type of the settings function.
+type Schemas = BTreeMap<(TypeId, TypeId), Arc<RootSchema>>;
+
+static SCHEMAS: RwLock<Schemas> = RwLock::new(BTreeMap::new());
+
+// The compiled patterns of all schemas, identified by the patterns.
+static REGEXES: RwLock<BTreeMap<String, Regex>> = RwLock::new(BTreeMap::new());
+
+/// Return the schema of the type generated with the default settings.
+///
+/// The schema is only generated once for every type, and it is shared
+/// between threads. Types are told apart by their [TypeId](std::any::TypeId),
+/// so generic types are cached for every set of type parameters,
+/// and types that share a [schema_id](JsonSchema::schema_id) are cached separately.
+///
+/// The derive macro uses this to avoid generating the schema on every verification.
+pub fn cached_schema<T: ?Sized + JsonSchema + 'static>() -> Arc<RootSchema> {
+    cached_schema_with::<T, _>(SchemaSettings::default)
+}
+
+/// Return the schema of the type generated with the settings
+/// returned by the function, such as [openapi3](SchemaSettings::openapi3).
+///
+/// The schemas are cached separately for every settings function,
+/// just like [cached_schema](cached_schema). Functions are told apart
+/// by their types, so only function items and closures without captures are cached,
+/// the schema is generated on every call for function pointers or closures with captures.
+pub fn cached_schema_with<T, F>(settings: F) -> Arc<RootSchema>
+where
+    T: ?Sized + JsonSchema + 'static,
+    F: Fn() -> SchemaSettings + 'static,
+{
+    let generate = || Arc::new(SchemaGenerator::new(settings()).into_root_schema_for::<T>());
+
+    // Function pointers share their type, and closures with captures
+    // can return different settings.
+    if std::mem::size_of::<F>() != 0 {
+        return generate();
+    }
+
+    let id = (TypeId::of::<T>(), TypeId::of::<F>());
+
+    if let Some(schema) = SCHEMAS.read().unwrap_or_else(|e| e.into_inner()).get(&id) {
+        return schema.clone();
+    }
+
+    // The schema is generated without holding the lock,
+    // so it is possibly generated more than once by different threads.
+    let schema = generate();
+
+    SCHEMAS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(id)
+        .or_insert(schema)
+        .clone()
+}
+
+/// Return the compiled pattern, it is only compiled once for every pattern.
+///
+/// Invalid patterns are not cached.
+pub(super) fn cached_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    if let Some(re) = REGEXES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(pattern)
+    {
+        return Ok(re.clone());
+    }
+
+    let re = Regex::new(pattern)?;
+
+    Ok(REGEXES
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(pattern.to_string())
+        .or_insert(re)
+        .clone())
+}
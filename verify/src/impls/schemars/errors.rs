@@ -1,108 +1,227 @@
 //! Error definitions used during Schema-related validation.
-
+//!
+//! All the errors can be serialized and deserialized with Serde.
+//! Every [ErrorValue](ErrorValue) has a stable machine-readable code (see [code](ErrorValue::code)),
+//! an error is serialized as a flat object:
+//!
+//! ```json
+//! {
+//!   "span": ["some_inner", "inner_values"],
+//!   "code": "max_items",
+//!   "params": { "max": 2 }
+//! }
+//! ```
+
+use super::messages::{Catalog, English, Localized};
 use crate::span::Span;
-use schemars_crate::schema::{InstanceType, Metadata, SingleOrVec};
+use schemars_crate::schema::{InstanceType, Metadata, SchemaObject, SingleOrVec};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ops::AddAssign;
 
 /// A validation error.
 ///
 /// It contains an optional span of the invalid value and optional information about the schema that caused the error.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "S: Serialize", deserialize = "S: Deserialize<'de>"))]
 pub struct Error<S: Span> {
     /// Information about the schema that caused the validation
     /// error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub meta: Option<Box<Metadata>>,
 
     /// The span of the invalid value.
+    #[serde(default)]
     pub span: Option<S>,
 
     /// The actual error details.
+    #[serde(flatten)]
     pub value: ErrorValue<S>,
+
+    /// A custom message provided by the schema
+    /// with the `errorMessage` extension keyword.
+    ///
+    /// If it is set, it is displayed instead of the default message.
+    ///
+    /// The extension can either be a string that is used for every error
+    /// of the schema, or an object of keywords and their messages:
+    ///
+    /// ```json
+    /// {
+    ///   "pattern": "^[a-zA-Z]{3,20}$",
+    ///   "errorMessage": {
+    ///     "pattern": "Username must be 3-20 letters",
+    ///     "required": { "name": "Name is required" }
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// Messages for `required` can also be given for each property.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Whether the value is invalid, or the error is only a warning.
+    ///
+    /// It is only serialized for warnings.
+    #[serde(default, skip_serializing_if = "Severity::is_error")]
+    pub severity: Severity,
+}
+
+/// The severity of an [Error](Error).
+///
+/// Values that have only warnings are valid, the warnings are
+/// returned by [SchemaVerifier::verify_with_warnings](super::SchemaVerifier::verify_with_warnings).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The value is invalid.
+    #[default]
+    Error,
+    /// The value is valid, but the client should be told about it,
+    /// for example it is deprecated.
+    Warning,
+}
+
+impl Severity {
+    /// Whether the value is invalid.
+    pub fn is_error(&self) -> bool {
+        *self == Severity::Error
+    }
 }
 
 impl<S: Span> Error<S> {
     pub(crate) fn new(meta: Option<Box<Metadata>>, span: Option<S>, value: ErrorValue<S>) -> Self {
-        Self { meta, span, value }
+        Self {
+            meta,
+            span,
+            value,
+            message: None,
+            severity: Severity::Error,
+        }
     }
-}
 
-impl<S: Span> core::fmt::Display for Error<S> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut start_paren = false;
+    /// Create an error that was caused by the given schema,
+    /// the custom message is taken from the schema if there is any.
+    pub(crate) fn schema(schema: &SchemaObject, span: Option<S>, value: ErrorValue<S>) -> Self {
+        let message = schema_message(schema, &value);
+
+        Self {
+            meta: schema.metadata.clone(),
+            span,
+            value,
+            message,
+            severity: Severity::Error,
+        }
+    }
 
-        if f.alternate() {
-            if let Some(span) = &self.span {
-                write!(f, "({:?}", span)?;
-                start_paren = true;
-            }
+    /// Whether the error is only a warning.
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
 
-            if let Some(meta) = &self.meta {
-                if let Some(title) = &meta.title {
-                    if start_paren {
-                        write!(f, r#", schema: "{}""#, title)?;
-                    } else {
-                        write!(f, r#"(schema: "{}""#, title)?;
-                    }
-                    start_paren = true;
-                }
+    pub(crate) fn warning(mut self) -> Self {
+        self.severity = Severity::Warning;
+        self
+    }
+}
+
+fn schema_message<S: Span>(schema: &SchemaObject, value: &ErrorValue<S>) -> Option<String> {
+    let keyword = value.keyword()?;
+
+    match schema.extensions.get("errorMessage")? {
+        serde_json::Value::String(message) => Some(message.clone()),
+        serde_json::Value::Object(messages) => match (messages.get(keyword)?, value) {
+            (serde_json::Value::String(message), _) => Some(message.clone()),
+            (serde_json::Value::Object(props), ErrorValue::RequiredProperty { name }) => {
+                props.get(name)?.as_str().map(String::from)
             }
-        }
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-        if start_paren {
-            write!(f, ") ")?;
-        }
+impl<S: Span> Error<S> {
+    /// Display the error with the given message catalog.
+    ///
+    /// The alternate flag (`{:#}`) also includes the span
+    /// and the schema title if there is any.
+    pub fn localized<'a, C: Catalog>(&'a self, catalog: &'a C) -> Localized<'a, Self, C> {
+        Localized::new(self, catalog)
+    }
+}
 
-        write!(f, "{}", self.value)
+impl<S: Span> core::fmt::Display for Error<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.localized(&English).fmt(f)
     }
 }
 
 /// All the validation errors that can occur.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "params")]
+#[serde(bound(serialize = "S: Serialize", deserialize = "S: Deserialize<'de>"))]
 // TODO maybe prefix or group them by type?
 pub enum ErrorValue<S: Span> {
     /// Indicates that the schema will never match any value.
+    #[serde(rename = "never")]
     Never,
 
     /// Indicates that the schema denies unknown properties.
+    #[serde(rename = "unknown_property")]
     UnknownProperty,
 
     /// Indicates that the schema itself is invalid.
+    #[serde(rename = "invalid_schema")]
     InvalidSchema(InvalidSchema),
 
     /// Indicates incompatible value that cannot be validated
     /// by a schema.
+    #[serde(rename = "unsupported_value")]
     UnsupportedValue(UnsupportedValue),
 
     /// Indicates invalid type.
+    #[serde(rename = "invalid_type")]
     InvalidType {
         expected: SingleOrVec<InstanceType>,
         actual: InstanceType,
     },
 
     /// Indicates invalid enum value.
+    #[serde(rename = "enum")]
     InvalidEnumValue { expected: Vec<serde_json::Value> },
 
+    /// Indicates that the value is not equal to the constant value.
+    #[serde(rename = "const")]
+    InvalidConstValue { expected: serde_json::Value },
+
     /// Indicates that the number is not multiple of the given value.
+    #[serde(rename = "multiple_of")]
     NotMultipleOf { multiple_of: f64 },
 
     /// Indicates that the number is less than the given minimum value.
+    #[serde(rename = "minimum")]
     LessThanExpected { min: f64, exclusive: bool },
 
     /// Indicates that the number is more than the given maximum value.
+    #[serde(rename = "maximum")]
     MoreThanExpected { max: f64, exclusive: bool },
 
     /// Indicates that the string doesn't match the given pattern.
+    #[serde(rename = "pattern")]
     NoPatternMatch { pattern: String },
 
     /// Indicates that the string is too long.
+    #[serde(rename = "max_length")]
     TooLong { max_length: u32 },
 
     /// Indicates that the string is too short.
+    #[serde(rename = "min_length")]
     TooShort { min_length: u32 },
 
     /// Indicates that none of the subschemas matched.
     ///
     /// Exclusive indicates that exactly one of them must have matched.
+    #[serde(rename = "none_valid")]
     NoneValid {
         exclusive: bool,
         schemas: Vec<Option<Box<Metadata>>>,
@@ -110,52 +229,96 @@ pub enum ErrorValue<S: Span> {
     },
 
     /// Indicates that more than one of the subschemas matched.
+    #[serde(rename = "more_than_one_valid")]
     MoreThanOneValid { schemas: Vec<Option<Box<Metadata>>>, matched: Vec<Option<Box<Metadata>>> },
 
+    /// Indicates that the discriminator property of a `oneOf`
+    /// does not select any of the subschemas.
+    #[serde(rename = "unknown_variant")]
+    UnknownVariant {
+        property: String,
+        tag: serde_json::Value,
+        expected: Vec<serde_json::Value>,
+    },
+
     /// Indicates that a not schema matched.
+    #[serde(rename = "not")]
     ValidNot { matched: Option<Box<Metadata>> },
 
     /// Indicates that the items in the array are not unique.
+    #[serde(rename = "unique_items")]
     NotUnique {
         first: Option<S>,
         duplicate: Option<S>,
     },
 
     /// Indicates that the array doesn't contain the value of a given schema.
+    #[serde(rename = "contains")]
     MustContain { schema: Option<Box<Metadata>> },
 
     /// Indicates that the array doesn't have enough items.
+    #[serde(rename = "min_items")]
     NotEnoughItems { min: usize },
 
     /// Indicates that the array has too many items.
+    #[serde(rename = "max_items")]
     TooManyItems { max: usize },
 
     /// Indicates that the object has too few properties.
+    #[serde(rename = "min_properties")]
     NotEnoughProperties { min: usize },
 
     /// Indicates that the object has too many properties.
+    #[serde(rename = "max_properties")]
     TooManyProperties { max: usize },
 
     /// Indicates that a required property is missing.
+    #[serde(rename = "required")]
     RequiredProperty { name: String },
 
+    /// Indicates that a property is present, but it is not allowed,
+    /// e.g. by a validation group.
+    #[serde(rename = "forbidden")]
+    ForbiddenProperty { name: String },
+
+    /// Indicates that a read-only property was sent in a request.
+    #[serde(rename = "read_only")]
+    ReadOnly,
+
+    /// Indicates that a write-only property was returned in a response.
+    #[serde(rename = "write_only")]
+    WriteOnly,
+
+    /// Indicates that the schema of the value is deprecated.
+    ///
+    /// It is always a warning.
+    #[serde(rename = "deprecated")]
+    Deprecated,
+
+    /// Indicates that a custom keyword rejected the value.
+    #[serde(rename = "keyword")]
+    Keyword { keyword: String, message: String },
+
     /// Any error that does not originate from the validator.
+    #[serde(rename = "custom")]
     Custom(String),
 }
 
 /// Error that occurs when a value cannot be validated
 /// by a schema.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
 pub enum UnsupportedValue {
     /// Indicates that key of a map is not a string.
     KeyNotString,
+
+    /// Indicates that a map value was validated without a key.
+    ValueWithoutKey,
 }
 
 impl core::fmt::Display for UnsupportedValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            UnsupportedValue::KeyNotString => write!(f, "map key must be a string"),
-        }
+        English.unsupported_value(self, f)
     }
 }
 
@@ -164,8 +327,10 @@ impl core::fmt::Display for UnsupportedValue {
 /// A schema must be valid in order to validate anything with it.
 /// This error occurs if that is not the case.
 ///
-/// It is also returned by calling [verify](crate::Verify::verify) on a schema.
-#[derive(Debug, Clone, PartialEq)]
+/// It is also returned by calling [verify](crate::Verify::verify) on a schema,
+/// or by [lint](super::lint), some of them are only warnings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "params", rename_all = "snake_case")]
 pub enum InvalidSchema {
     /// Indicates a missing local definition.
     MissingDefinition(String),
@@ -173,165 +338,182 @@ pub enum InvalidSchema {
     /// Indicates an invalid regex pattern in the schema.
     InvalidPattern {
         pattern: String,
+        #[serde(with = "regex_error")]
         error: regex::Error,
     },
 
     /// Indicates an unresolved external reference in the schema.
     ExternalReference(String),
+
+    /// Indicates that the lower limit is greater than the upper one,
+    /// e.g. `minLength` is greater than `maxLength`.
+    EmptyRange { min: String, max: String },
+
+    /// Indicates an `enum` or `const` value that does not match the `type`.
+    ///
+    /// It is a warning.
+    TypeMismatch {
+        keyword: String,
+        value: serde_json::Value,
+    },
+
+    /// Indicates a required property that is not allowed
+    /// because of `additionalProperties`.
+    RequiredNotAllowed(String),
+
+    /// Indicates a `oneOf` subschema that can never be the only one that matches.
+    ///
+    /// It is a warning.
+    UnreachableOneOf { index: usize },
+
+    /// Indicates a `oneOf` subschema that is the same as another one,
+    /// so neither of them can be the only one that matches.
+    ///
+    /// It is a warning.
+    DuplicateOneOf { index: usize, other: usize },
+
+    /// Indicates a keyword that has no effect for the `type`,
+    /// e.g. a `pattern` for numbers.
+    ///
+    /// It is a warning.
+    IgnoredKeyword(String),
+
+    /// Indicates a definition that always contains itself,
+    /// so no finite value can be valid.
+    RefCycle(String),
+}
+
+/// The regex error is serialized as its message.
+mod regex_error {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        error: &regex::Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(error)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<regex::Error, D::Error> {
+        String::deserialize(deserializer).map(regex::Error::Syntax)
+    }
 }
 
 impl core::fmt::Display for InvalidSchema {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            InvalidSchema::MissingDefinition(s) => write!(f, r#"missing local definition "{}""#, s),
-            InvalidSchema::InvalidPattern { pattern, error } => {
-                write!(f, r#"invalid regex pattern "{}": {}"#, pattern, error)
-            }
-            InvalidSchema::ExternalReference(r) => write!(
-                f,
-                r#"the schema contains unresolved external reference: "{}""#,
-                r
-            ),
-        }
+        English.invalid_schema(self, f)
     }
 }
 
 impl<S: Span> core::fmt::Display for ErrorValue<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        English.error_value(self, f)
+    }
+}
+
+impl<S: Span> ErrorValue<S> {
+    /// A stable machine-readable code of the error.
+    ///
+    /// It is the same as the `code` field of the serialized error.
+    pub fn code(&self) -> &'static str {
         match self {
-            ErrorValue::Never => write!(f, "no values allowed"),
-            ErrorValue::UnknownProperty => write!(f, "unknown property"),
-            ErrorValue::InvalidSchema(err) => write!(f, "invalid schema: {}", err),
-            ErrorValue::UnsupportedValue(err) => write!(f, "unsupported value: {}", err),
-            ErrorValue::InvalidType { expected, actual } => write!(
-                f,
-                r#"invalid type, expected {}, not "{:?}""#,
-                match expected {
-                    SingleOrVec::Single(s) => {
-                        format!(r#""{:?}""#, s)
-                    }
-                    SingleOrVec::Vec(v) => {
-                        let mut s = "one of {".into();
-
-                        for (i, t) in v.iter().enumerate() {
-                            s += format!(r#""{:?}""#, t).as_str();
-                            if i != v.len() - 1 {
-                                s += ", "
-                            }
-                        }
-                        s += "}";
-
-                        s
-                    }
-                },
-                actual
-            ),
-            ErrorValue::InvalidEnumValue { expected } => {
-                let enum_vals: Vec<String> = expected.iter().map(|v| v.to_string()).collect();
-                write!(
-                    f,
-                    "invalid enum value, expected to be one of {{{}}}",
-                    enum_vals.join(", ")
-                )
-            }
-            ErrorValue::NotMultipleOf { multiple_of } => {
-                write!(f, "the value is expected to be multiple of {}", multiple_of)
-            }
-            ErrorValue::LessThanExpected { min, exclusive } => {
+            ErrorValue::Never => "never",
+            ErrorValue::UnknownProperty => "unknown_property",
+            ErrorValue::InvalidSchema(_) => "invalid_schema",
+            ErrorValue::UnsupportedValue(_) => "unsupported_value",
+            ErrorValue::InvalidType { .. } => "invalid_type",
+            ErrorValue::InvalidEnumValue { .. } => "enum",
+            ErrorValue::InvalidConstValue { .. } => "const",
+            ErrorValue::NotMultipleOf { .. } => "multiple_of",
+            ErrorValue::LessThanExpected { .. } => "minimum",
+            ErrorValue::MoreThanExpected { .. } => "maximum",
+            ErrorValue::NoPatternMatch { .. } => "pattern",
+            ErrorValue::TooLong { .. } => "max_length",
+            ErrorValue::TooShort { .. } => "min_length",
+            ErrorValue::NoneValid { .. } => "none_valid",
+            ErrorValue::MoreThanOneValid { .. } => "more_than_one_valid",
+            ErrorValue::UnknownVariant { .. } => "unknown_variant",
+            ErrorValue::ValidNot { .. } => "not",
+            ErrorValue::NotUnique { .. } => "unique_items",
+            ErrorValue::MustContain { .. } => "contains",
+            ErrorValue::NotEnoughItems { .. } => "min_items",
+            ErrorValue::TooManyItems { .. } => "max_items",
+            ErrorValue::NotEnoughProperties { .. } => "min_properties",
+            ErrorValue::TooManyProperties { .. } => "max_properties",
+            ErrorValue::RequiredProperty { .. } => "required",
+            ErrorValue::ForbiddenProperty { .. } => "forbidden",
+            ErrorValue::ReadOnly => "read_only",
+            ErrorValue::WriteOnly => "write_only",
+            ErrorValue::Deprecated => "deprecated",
+            ErrorValue::Keyword { .. } => "keyword",
+            ErrorValue::Custom(_) => "custom",
+        }
+    }
+
+    /// The schema keyword that caused the error.
+    ///
+    /// It is [None](Option::None) for errors that are not caused
+    /// by a validation keyword.
+    pub fn keyword(&self) -> Option<&str> {
+        match self {
+            ErrorValue::Never
+            | ErrorValue::InvalidSchema(_)
+            | ErrorValue::UnsupportedValue(_)
+            | ErrorValue::ForbiddenProperty { .. }
+            | ErrorValue::Custom(_) => None,
+            ErrorValue::UnknownProperty => Some("additionalProperties"),
+            ErrorValue::InvalidType { .. } => Some("type"),
+            ErrorValue::InvalidEnumValue { .. } => Some("enum"),
+            ErrorValue::InvalidConstValue { .. } => Some("const"),
+            ErrorValue::NotMultipleOf { .. } => Some("multipleOf"),
+            ErrorValue::LessThanExpected { exclusive, .. } => {
                 if *exclusive {
-                    write!(f, "the value is expected to be more than {}", min)
+                    Some("exclusiveMinimum")
                 } else {
-                    write!(f, "the value is expected to be at least {}", min)
+                    Some("minimum")
                 }
             }
-            ErrorValue::MoreThanExpected { max, exclusive } => {
+            ErrorValue::MoreThanExpected { exclusive, .. } => {
                 if *exclusive {
-                    write!(f, "the value is expected to be less than {}", max)
+                    Some("exclusiveMaximum")
                 } else {
-                    write!(f, "the value is expected to be at most {}", max)
-                }
-            }
-            ErrorValue::NoPatternMatch { pattern } => {
-                write!(f, r#"the string must match the pattern "{}""#, pattern)
-            }
-            ErrorValue::TooLong { max_length } => write!(
-                f,
-                r#"the string must not be longer than {} characters"#,
-                max_length
-            ),
-            ErrorValue::TooShort { min_length } => write!(
-                f,
-                r#"the string must must be at least {} characters long"#,
-                min_length
-            ),
-            ErrorValue::NoneValid {
-                exclusive: _,
-                schemas: _,
-                errors,
-            } => {
-                writeln!(f, r#"no subschema matched the value:"#)?;
-
-                for (i, e) in errors.iter().enumerate() {
-                    write!(f, "{}", e)?;
-
-                    if i != errors.len() - 1 {
-                        writeln!(f, "\n")?;
-                    }
-                }
-
-                Ok(())
-            }
-            ErrorValue::MoreThanOneValid { schemas: _, matched } => writeln!(
-                f,
-                r#"expected exactly one schema to match, but {} schemas matched"#,
-                matched.len()
-            ),
-            ErrorValue::ValidNot { matched } => {
-                if let Some(meta) = matched {
-                    if let Some(title) = &meta.title {
-                        return writeln!(f, r#"the value must not be a "{}""#, title);
-                    }
+                    Some("maximum")
                 }
-
-                writeln!(f, r#"the value is disallowed by a "not" schema"#)
             }
-            ErrorValue::NotUnique { first: _, duplicate: _ } => {
-                writeln!(f, r#"all items in the array must be unique"#)
-            }
-            ErrorValue::MustContain { schema } => {
-                if let Some(meta) = schema {
-                    if let Some(title) = &meta.title {
-                        return writeln!(
-                            f,
-                            r#"at least one of the items in the array must be "{}""#,
-                            title
-                        );
-                    }
+            ErrorValue::NoPatternMatch { .. } => Some("pattern"),
+            ErrorValue::TooLong { .. } => Some("maxLength"),
+            ErrorValue::TooShort { .. } => Some("minLength"),
+            ErrorValue::NoneValid { exclusive, .. } => {
+                if *exclusive {
+                    Some("oneOf")
+                } else {
+                    Some("anyOf")
                 }
-
-                writeln!(
-                    f,
-                    r#"at least one of the items in the array must match the given schema"#
-                )
-            }
-            ErrorValue::NotEnoughItems { min } => {
-                write!(f, "the array must have at least {} items", min)
-            }
-            ErrorValue::TooManyItems { max } => {
-                write!(f, "the array cannot have more than {} items", max)
             }
-            ErrorValue::NotEnoughProperties { min } => {
-                write!(f, "the object must have at least {} properties", min)
+            ErrorValue::MoreThanOneValid { .. } | ErrorValue::UnknownVariant { .. } => {
+                Some("oneOf")
             }
-            ErrorValue::TooManyProperties { max } => {
-                write!(f, "the object cannot have more than {} properties", max)
-            }
-            ErrorValue::RequiredProperty { name } => {
-                write!(f, r#"the required property "{}" is missing"#, name)
-            }
-            ErrorValue::Custom(err) => err.fmt(f),
+            ErrorValue::ValidNot { .. } => Some("not"),
+            ErrorValue::NotUnique { .. } => Some("uniqueItems"),
+            ErrorValue::MustContain { .. } => Some("contains"),
+            ErrorValue::NotEnoughItems { .. } => Some("minItems"),
+            ErrorValue::TooManyItems { .. } => Some("maxItems"),
+            ErrorValue::NotEnoughProperties { .. } => Some("minProperties"),
+            ErrorValue::TooManyProperties { .. } => Some("maxProperties"),
+            ErrorValue::RequiredProperty { .. } => Some("required"),
+            ErrorValue::ReadOnly => Some("readOnly"),
+            ErrorValue::WriteOnly => Some("writeOnly"),
+            ErrorValue::Deprecated => Some("deprecated"),
+            ErrorValue::Keyword { keyword, .. } => Some(keyword),
         }
     }
+
+    /// Display the error with the given message catalog.
+    pub fn localized<'a, C: Catalog>(&'a self, catalog: &'a C) -> Localized<'a, Self, C> {
+        Localized::new(self, catalog)
+    }
 }
 
 #[cfg(feature = "smallvec")]
@@ -363,6 +545,70 @@ impl<S: Span> Errors<S> {
         self.0.iter()
     }
 
+    /// Whether there are errors that are not warnings.
+    pub fn has_errors(&self) -> bool {
+        self.iter().any(|e| !e.is_warning())
+    }
+
+    /// Iterate over the warnings only.
+    pub fn warnings(&self) -> impl Iterator<Item = &Error<S>> {
+        self.iter().filter(|e| e.is_warning())
+    }
+
+    /// Return the errors with every `anyOf` and `oneOf` failure
+    /// replaced by the errors of the most relevant subschema.
+    ///
+    /// When none of the subschemas match, [NoneValid](ErrorValue::NoneValid)
+    /// contains the errors of every subschema, which is mostly noise
+    /// for enums with a lot of variants.
+    ///
+    /// The subschema that matched the furthest is chosen: the one with the fewest
+    /// errors that indicate a different kind of value (wrong type, constant, enum value
+    /// or unknown property), then the one with the fewest errors overall.
+    /// The first one wins on ties.
+    ///
+    /// Errors with a custom message from the schema are kept as they are.
+    pub fn best_match(&self) -> Self {
+        let mut best = ErrorsInner::new();
+
+        for e in self.iter() {
+            match (&e.value, &e.message) {
+                (ErrorValue::NoneValid { errors, .. }, None) if !errors.is_empty() => {
+                    let branch = errors
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(i, errs)| (errs.mismatches(), errs.len(), *i))
+                        .map(|(_, errs)| errs)
+                        .unwrap();
+
+                    best.extend(branch.best_match().0);
+                }
+                _ => best.push(e.clone()),
+            }
+        }
+
+        Errors(best)
+    }
+
+    /// The count of errors that suggest that the
+    /// value is not meant for the schema at all.
+    fn mismatches(&self) -> usize {
+        self.iter()
+            .filter(|e| match &e.value {
+                ErrorValue::Never
+                | ErrorValue::UnknownProperty
+                | ErrorValue::InvalidType { .. }
+                | ErrorValue::InvalidEnumValue { .. }
+                | ErrorValue::InvalidConstValue { .. }
+                | ErrorValue::UnknownVariant { .. } => true,
+                ErrorValue::NoneValid { errors, .. } => {
+                    errors.iter().map(Errors::mismatches).min().unwrap_or(0) > 0
+                }
+                _ => false,
+            })
+            .count()
+    }
+
     pub(super) fn new() -> Self {
         Errors(ErrorsInner::new())
     }
@@ -388,12 +634,28 @@ impl<S: Span> IntoIterator for Errors<S> {
     }
 }
 
+impl<S: Span> Errors<S> {
+    /// Display all the errors with the given message catalog.
+    pub fn localized<'a, C: Catalog>(&'a self, catalog: &'a C) -> Localized<'a, Self, C> {
+        Localized::new(self, catalog)
+    }
+}
+
 impl<S: Span> core::fmt::Display for Errors<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for e in &self.0 {
-            writeln!(f, "{}", e)?;
-        }
-        Ok(())
+        self.localized(&English).fmt(f)
+    }
+}
+
+impl<S: Span + Serialize> Serialize for Errors<S> {
+    fn serialize<SE: Serializer>(&self, serializer: SE) -> Result<SE::Ok, SE::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, S: Span + Deserialize<'de>> Deserialize<'de> for Errors<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Error<S>>::deserialize(deserializer).map(|v| Errors(v.into_iter().collect()))
     }
 }
 
@@ -408,6 +670,32 @@ impl<S: Span> crate::Error for Errors<S> {
     }
 }
 
+impl<S: Span> crate::SpannedError<S> for Errors<S> {
+    fn spanned(span: Option<S>, message: String) -> Self {
+        Self::one(Error::new(None, span, ErrorValue::Custom(message)))
+    }
+
+    fn missing_property(span: Option<S>, name: &str) -> Self {
+        Self::one(Error::new(
+            None,
+            span,
+            ErrorValue::RequiredProperty {
+                name: name.to_string(),
+            },
+        ))
+    }
+
+    fn forbidden_property(span: Option<S>, name: &str) -> Self {
+        Self::one(Error::new(
+            None,
+            span,
+            ErrorValue::ForbiddenProperty {
+                name: name.to_string(),
+            },
+        ))
+    }
+}
+
 impl<S: Span> AddAssign for Errors<S> {
     fn add_assign(&mut self, rhs: Self) {
         self.0.extend(rhs.0.into_iter());
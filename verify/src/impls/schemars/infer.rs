@@ -0,0 +1,264 @@
+//! Inference of schemas from sample documents.
+//!
+//! ```ignore
+//! use verify::schemars::infer::Inference;
+//!
+//! let mut inference = Inference::new().max_enum_values(5);
+//!
+//! for sample in &samples {
+//!     inference.add(sample);
+//! }
+//!
+//! let schema = inference.root_schema();
+//! schema.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//! ```
+//!
+//! Properties are required if they were present in every sample of the object,
+//! and strings that only had a few distinct values that repeated are enums.
+//! The samples of array items are merged into a single item schema.
+
+use schemars_crate::schema::{
+    ArrayValidation, InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject, SingleOrVec,
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Infers a schema from sample documents.
+#[derive(Debug, Clone)]
+pub struct Inference {
+    max_enum_values: usize,
+    deny_unknown: bool,
+    shape: Shape,
+}
+
+impl Default for Inference {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inference {
+    /// Create an inference with the default options.
+    pub fn new() -> Self {
+        Self {
+            max_enum_values: 5,
+            deny_unknown: false,
+            shape: Shape::default(),
+        }
+    }
+
+    /// Set the maximum count of distinct values of enum strings, the default is 5.
+    ///
+    /// Only strings that were seen at least twice as many times as they
+    /// have distinct values are enums, `0` disables enums.
+    pub fn max_enum_values(mut self, max: usize) -> Self {
+        self.max_enum_values = max;
+        self
+    }
+
+    /// Do not allow properties that were not in the samples.
+    ///
+    /// Unknown properties are allowed by default.
+    pub fn deny_unknown(mut self, deny: bool) -> Self {
+        self.deny_unknown = deny;
+        self
+    }
+
+    /// Add a sample document.
+    pub fn add(&mut self, sample: &Value) {
+        self.shape.add(sample, self.max_enum_values);
+    }
+
+    /// Add sample documents.
+    pub fn samples<'v>(mut self, samples: impl IntoIterator<Item = &'v Value>) -> Self {
+        for sample in samples {
+            self.add(sample);
+        }
+        self
+    }
+
+    /// The schema of all the samples that were added.
+    ///
+    /// Without any samples every value is allowed.
+    pub fn root_schema(&self) -> RootSchema {
+        RootSchema {
+            meta_schema: Some("http://json-schema.org/draft-07/schema#".into()),
+            schema: self.shape.schema(self),
+            definitions: Default::default(),
+        }
+    }
+}
+
+/// Infer a schema from the samples with the default options.
+pub fn infer<'v>(samples: impl IntoIterator<Item = &'v Value>) -> RootSchema {
+    Inference::new().samples(samples).root_schema()
+}
+
+/// Everything that was seen at the same place in the samples.
+#[derive(Debug, Clone, Default)]
+struct Shape {
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool,
+    string: Option<Strings>,
+    // The items of all the arrays.
+    array: Option<Box<Shape>>,
+    object: Option<Object>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Strings {
+    count: usize,
+    // It is cleared if there are too many distinct values.
+    values: Option<BTreeSet<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Object {
+    count: usize,
+    properties: BTreeMap<String, (usize, Shape)>,
+}
+
+impl Shape {
+    fn add(&mut self, value: &Value, max_enum_values: usize) {
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(n) if n.is_f64() => self.number = true,
+            Value::Number(_) => self.integer = true,
+            Value::String(s) => {
+                let strings = self.string.get_or_insert_with(|| Strings {
+                    count: 0,
+                    values: Some(BTreeSet::new()),
+                });
+
+                strings.count += 1;
+
+                if let Some(values) = &mut strings.values {
+                    values.insert(s.clone());
+
+                    if values.len() > max_enum_values {
+                        strings.values = None;
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                let items = self.array.get_or_insert_with(Default::default);
+
+                for item in arr {
+                    items.add(item, max_enum_values);
+                }
+            }
+            Value::Object(obj) => {
+                let object = self.object.get_or_insert_with(Default::default);
+                object.count += 1;
+
+                for (key, value) in obj {
+                    let (count, shape) = object.properties.entry(key.clone()).or_default();
+                    *count += 1;
+                    shape.add(value, max_enum_values);
+                }
+            }
+        }
+    }
+
+    fn schema(&self, options: &Inference) -> SchemaObject {
+        let mut types = Vec::new();
+        let mut schema = SchemaObject::default();
+
+        if self.null {
+            types.push(InstanceType::Null);
+        }
+
+        if self.boolean {
+            types.push(InstanceType::Boolean);
+        }
+
+        // Integers are also numbers.
+        if self.number {
+            types.push(InstanceType::Number);
+        } else if self.integer {
+            types.push(InstanceType::Integer);
+        }
+
+        if let Some(strings) = &self.string {
+            types.push(InstanceType::String);
+
+            // The enum applies to every value, so it is only used
+            // if the strings can only be null otherwise.
+            let only_strings = !self.boolean
+                && !self.integer
+                && !self.number
+                && self.array.is_none()
+                && self.object.is_none();
+
+            match &strings.values {
+                Some(values)
+                    if only_strings && !values.is_empty() && strings.count >= values.len() * 2 =>
+                {
+                    let mut enum_values: Vec<_> =
+                        values.iter().cloned().map(Value::String).collect();
+
+                    if self.null {
+                        enum_values.push(Value::Null);
+                    }
+
+                    schema.enum_values = Some(enum_values);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(items) = &self.array {
+            types.push(InstanceType::Array);
+
+            if !items.is_empty() {
+                schema.array = Some(Box::new(ArrayValidation {
+                    items: Some(SingleOrVec::Single(Box::new(items.schema(options).into()))),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        if let Some(object) = &self.object {
+            types.push(InstanceType::Object);
+
+            let mut validation = ObjectValidation::default();
+
+            for (key, (count, shape)) in &object.properties {
+                if *count == object.count {
+                    validation.required.insert(key.clone());
+                }
+
+                validation
+                    .properties
+                    .insert(key.clone(), shape.schema(options).into());
+            }
+
+            if options.deny_unknown {
+                validation.additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
+
+            schema.object = Some(Box::new(validation));
+        }
+
+        schema.instance_type = match types.len() {
+            0 => None,
+            1 => Some(SingleOrVec::Single(Box::new(types[0]))),
+            _ => Some(SingleOrVec::Vec(types)),
+        };
+
+        schema
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.null
+            && !self.boolean
+            && !self.integer
+            && !self.number
+            && self.string.is_none()
+            && self.array.is_none()
+            && self.object.is_none()
+    }
+}
@@ -0,0 +1,71 @@
+//! Custom keywords for schema validation.
+//!
+//! Schemars keeps unknown keywords in [extensions](schemars_crate::schema::SchemaObject::extensions),
+//! handlers can be registered for them with [SchemaVerifier::keyword](super::SchemaVerifier::keyword).
+//!
+//! ```ignore
+//! let verifier = SchemaVerifier::new(&root).keyword("x-luhn", |enabled: &Value, instance: &Value| {
+//!     match instance.as_str() {
+//!         Some(digits) if enabled == &Value::Bool(true) && !luhn(digits) => {
+//!             Err("invalid card number".into())
+//!         }
+//!         _ => Ok(()),
+//!     }
+//! });
+//!
+//! verifier.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//! ```
+//!
+//! Handlers wrapped in [Warn](Warn) only report warnings, they never make a value invalid.
+
+use super::errors::Severity;
+use serde_json::Value;
+use std::{collections::HashMap, sync::Arc};
+
+/// A handler for a custom schema keyword.
+///
+/// It is implemented for closures with the same signature as [validate](Keyword::validate).
+pub trait Keyword: Send + Sync {
+    /// Validate an instance.
+    ///
+    /// The first argument is the value of the keyword in the schema,
+    /// the second one is the instance that is being validated.
+    ///
+    /// The returned error is used as the message of the validation error.
+    fn validate(&self, value: &Value, instance: &Value) -> Result<(), String>;
+
+    /// The severity of the errors of the keyword.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+}
+
+impl<F> Keyword for F
+where
+    F: Fn(&Value, &Value) -> Result<(), String> + Send + Sync,
+{
+    fn validate(&self, value: &Value, instance: &Value) -> Result<(), String> {
+        self(value, instance)
+    }
+}
+
+/// A keyword handler that reports warnings instead of errors.
+///
+/// ```ignore
+/// let verifier = SchemaVerifier::new(&root).keyword("x-soft-max-length", Warn(soft_max_length));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Warn<K>(pub K);
+
+impl<K: Keyword> Keyword for Warn<K> {
+    fn validate(&self, value: &Value, instance: &Value) -> Result<(), String> {
+        self.0.validate(value, instance)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+/// Registered keyword handlers by their names.
+pub(super) type Keywords = HashMap<String, Arc<dyn Keyword>>;
@@ -28,11 +28,17 @@ macro_rules! check_type {
         match &$schema.instance_type {
             Some(s) => match s {
                 SingleOrVec::Single(single) => match &**single {
-                    InstanceType::$actual_type => Ok(()),
+                    // Integers are also numbers.
+                    t if *t == InstanceType::$actual_type
+                        || (*t == InstanceType::Number
+                            && InstanceType::$actual_type == InstanceType::Integer) =>
+                    {
+                        Ok(())
+                    }
                     _ => {
                         let mut errors = ErrorsInner::new();
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
+                        errors.push(Error::schema(
+                            $schema,
                             $span.clone(),
                             ErrorValue::InvalidType {
                                 expected: s.clone(),
@@ -43,12 +49,17 @@ macro_rules! check_type {
                     }
                 },
                 SingleOrVec::Vec(vec) => {
-                    if vec.iter().any(|i| *i == InstanceType::$actual_type) {
+                    // Integers are also numbers.
+                    if vec.iter().any(|i| {
+                        *i == InstanceType::$actual_type
+                            || (*i == InstanceType::Number
+                                && InstanceType::$actual_type == InstanceType::Integer)
+                    }) {
                         Ok(())
                     } else {
                         let mut errors = ErrorsInner::new();
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
+                        errors.push(Error::schema(
+                            $schema,
                             $span.clone(),
                             ErrorValue::InvalidType {
                                 expected: s.clone(),
@@ -61,8 +72,8 @@ macro_rules! check_type {
             },
             None => {
                 let mut errors = ErrorsInner::new();
-                errors.push(Error::new(
-                    $schema.metadata.clone(),
+                errors.push(Error::schema(
+                    $schema,
                     $span.clone(),
                     ErrorValue::InvalidType {
                         expected: SingleOrVec::Single(Box::new(InstanceType::Object)),
@@ -80,8 +91,8 @@ macro_rules! check_type {
                     InstanceType::Object => Ok(()),
                     _ => {
                         let mut errors = ErrorsInner::new();
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
+                        errors.push(Error::schema(
+                            $schema,
                             $span.clone(),
                             ErrorValue::InvalidType {
                                 expected: s.clone(),
@@ -96,8 +107,8 @@ macro_rules! check_type {
                         Ok(())
                     } else {
                         let mut errors = ErrorsInner::new();
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
+                        errors.push(Error::schema(
+                            $schema,
                             $span.clone(),
                             ErrorValue::InvalidType {
                                 expected: s.clone(),
@@ -113,133 +124,6 @@ macro_rules! check_type {
     };
 }
 
-macro_rules! check_enum {
-    (bool, $value:expr, $schema:expr, $span:expr) => {
-        if let Some(enum_vals) = &$schema.enum_values {
-            let mut enum_contains = false;
-            for val in enum_vals {
-                if let Some(v) = val.as_bool() {
-                    if v == $value {
-                        enum_contains = true;
-                        break;
-                    }
-                }
-            }
-
-            if enum_contains {
-                Ok(())
-            } else {
-                let mut errors = ErrorsInner::new();
-                errors.push(Error::new(
-                    $schema.metadata.clone(),
-                    $span.clone(),
-                    ErrorValue::InvalidEnumValue {
-                        expected: enum_vals.clone(),
-                    },
-                ));
-                Err(Errors(errors))
-            }
-        } else {
-            Ok(())
-        }
-    };
-    (int, $value:expr, $schema:expr, $span:expr) => {
-        if let Some(enum_vals) = &$schema.enum_values {
-            let mut errors = ErrorsInner::new();
-
-            let mut enum_contains = false;
-            for val in enum_vals {
-                if let Some(v) = val.as_i64() {
-                    if v == $value as i64 {
-                        enum_contains = true;
-                        break;
-                    }
-                }
-                if let Some(v) = val.as_u64() {
-                    if v == $value as u64 {
-                        enum_contains = true;
-                        break;
-                    }
-                }
-            }
-
-            if enum_contains {
-                Ok(())
-            } else {
-                errors.push(Error::new(
-                    $schema.metadata.clone(),
-                    $span.clone(),
-                    ErrorValue::InvalidEnumValue {
-                        expected: enum_vals.clone(),
-                    },
-                ));
-                Err(Errors(errors))
-            }
-        } else {
-            Ok(())
-        }
-    };
-    (float, $value:expr, $schema:expr, $span:expr) => {
-        if let Some(enum_vals) = &$schema.enum_values {
-            let mut errors = ErrorsInner::new();
-
-            let mut enum_contains = false;
-            for val in enum_vals {
-                if let Some(v) = val.as_f64() {
-                    if f64::abs(v - $value as f64) < core::f64::EPSILON {
-                        enum_contains = true;
-                        break;
-                    }
-                }
-            }
-
-            if enum_contains {
-                Ok(())
-            } else {
-                errors.push(Error::new(
-                    $schema.metadata.clone(),
-                    $span.clone(),
-                    ErrorValue::InvalidEnumValue {
-                        expected: enum_vals.clone(),
-                    },
-                ));
-                Err(Errors(errors))
-            }
-        } else {
-            Ok(())
-        }
-    };
-    (str, $value:expr, $schema:expr, $span:expr) => {
-        if let Some(enum_vals) = &$schema.enum_values {
-            let mut enum_contains = false;
-            for val in enum_vals {
-                if let Some(v) = val.as_str() {
-                    if v == $value {
-                        enum_contains = true;
-                        break;
-                    }
-                }
-            }
-
-            if enum_contains {
-                Ok(())
-            } else {
-                let mut errors = ErrorsInner::new();
-                errors.push(Error::new(
-                    $schema.metadata.clone(),
-                    $span.clone(),
-                    ErrorValue::InvalidEnumValue {
-                        expected: enum_vals.clone(),
-                    },
-                ));
-                Err(Errors(errors))
-            }
-        } else {
-            Ok(())
-        }
-    };
-}
-
 macro_rules! check_number {
     ($value:expr, $schema:expr, $span:expr) => {
         if let Some(n) = &$schema.number {
@@ -249,8 +133,8 @@ macro_rules! check_number {
 
             if let Some(m) = n.multiple_of {
                 if m != 0f64 && $value as f64 % m != 0f64 {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::NotMultipleOf { multiple_of: m },
                     ));
@@ -260,8 +144,8 @@ macro_rules! check_number {
 
             if let Some(min) = n.minimum {
                 if ($value as f64) < min {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::LessThanExpected {
                             min,
@@ -274,8 +158,8 @@ macro_rules! check_number {
 
             if let Some(min) = n.exclusive_minimum {
                 if ($value as f64) <= min {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::LessThanExpected {
                             min,
@@ -288,8 +172,8 @@ macro_rules! check_number {
 
             if let Some(max) = n.maximum {
                 if ($value as f64) > max {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::MoreThanExpected {
                             max,
@@ -302,8 +186,8 @@ macro_rules! check_number {
 
             if let Some(max) = n.exclusive_maximum {
                 if ($value as f64) >= max {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::MoreThanExpected {
                             max,
@@ -320,7 +204,7 @@ macro_rules! check_number {
             }
         } else {
             Ok(())
-        };
+        }
     };
 }
 
@@ -333,9 +217,9 @@ macro_rules! check_string {
             let mut string_err = false;
 
             if let Some(p) = &s.pattern {
-                let re = regex::Regex::new(&*p).map_err(|error| {
-                    Errors::one(Error::new(
-                        $schema.metadata.clone(),
+                let re = super::cache::cached_regex(p).map_err(|error| {
+                    Errors::one(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::InvalidSchema(InvalidSchema::InvalidPattern {
                             pattern: p.clone(),
@@ -345,34 +229,34 @@ macro_rules! check_string {
                 })?;
 
                 if !re.is_match($value) {
-                    errors.push(Error::new(
-                        $schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        $schema,
                         $span.clone(),
                         ErrorValue::NoPatternMatch { pattern: p.clone() },
                     ));
                     string_err = true;
                 }
+            }
 
-                if let Some(max_length) = s.max_length {
-                    if $value.chars().count() > max_length as usize {
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
-                            $span.clone(),
-                            ErrorValue::TooLong { max_length },
-                        ));
-                        string_err = true;
-                    }
+            if let Some(max_length) = s.max_length {
+                if $value.chars().count() > max_length as usize {
+                    errors.push(Error::schema(
+                        $schema,
+                        $span.clone(),
+                        ErrorValue::TooLong { max_length },
+                    ));
+                    string_err = true;
                 }
+            }
 
-                if let Some(min_length) = s.min_length {
-                    if $value.chars().count() < min_length as usize {
-                        errors.push(Error::new(
-                            $schema.metadata.clone(),
-                            $span.clone(),
-                            ErrorValue::TooShort { min_length },
-                        ));
-                        string_err = true;
-                    }
+            if let Some(min_length) = s.min_length {
+                if $value.chars().count() < min_length as usize {
+                    errors.push(Error::schema(
+                        $schema,
+                        $span.clone(),
+                        ErrorValue::TooShort { min_length },
+                    ));
+                    string_err = true;
                 }
             }
 
@@ -0,0 +1,349 @@
+//! Human-readable messages for validation errors.
+//!
+//! Every error is rendered through a [Catalog](Catalog), the [Display](core::fmt::Display)
+//! implementations of the error types use the built-in [English](English) catalog.
+//! Custom messages from the schema (`errorMessage`) take precedence over the catalog.
+//!
+//! Custom catalogs receive the structured errors, so all the parameters
+//! (limits, patterns, property names, etc.) are available for translations:
+//!
+//! ```ignore
+//! struct Hungarian;
+//!
+//! impl Catalog for Hungarian {
+//!     fn error_value<S: Span>(&self, error: &ErrorValue<S>, f: &mut Formatter<'_>) -> fmt::Result {
+//!         match error {
+//!             ErrorValue::RequiredProperty { name } => {
+//!                 write!(f, r#"a kötelező "{}" mező hiányzik"#, name)
+//!             }
+//!             _ => English.error_value(error, f),
+//!         }
+//!     }
+//!     // ...
+//! }
+//!
+//! println!("{}", errors.localized(&Hungarian));
+//! ```
+
+use super::errors::{Error, ErrorValue, Errors, InvalidSchema, UnsupportedValue};
+use crate::span::Span;
+use core::fmt::{self, Display, Formatter};
+use schemars_crate::schema::SingleOrVec;
+
+/// A message catalog that renders errors as human-readable messages.
+pub trait Catalog {
+    /// Write the message for a validation error.
+    fn error_value<S: Span>(&self, error: &ErrorValue<S>, f: &mut Formatter<'_>) -> fmt::Result;
+
+    /// Write the message for an invalid schema error.
+    fn invalid_schema(&self, error: &InvalidSchema, f: &mut Formatter<'_>) -> fmt::Result;
+
+    /// Write the message for an unsupported value error.
+    fn unsupported_value(&self, error: &UnsupportedValue, f: &mut Formatter<'_>) -> fmt::Result;
+}
+
+/// The built-in English messages.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct English;
+
+impl Catalog for English {
+    fn error_value<S: Span>(&self, error: &ErrorValue<S>, f: &mut Formatter<'_>) -> fmt::Result {
+        match error {
+            ErrorValue::Never => write!(f, "no values allowed"),
+            ErrorValue::UnknownProperty => write!(f, "unknown property"),
+            ErrorValue::InvalidSchema(err) => {
+                write!(f, "invalid schema: ")?;
+                self.invalid_schema(err, f)
+            }
+            ErrorValue::UnsupportedValue(err) => {
+                write!(f, "unsupported value: ")?;
+                self.unsupported_value(err, f)
+            }
+            ErrorValue::InvalidType { expected, actual } => write!(
+                f,
+                r#"invalid type, expected {}, not "{:?}""#,
+                match expected {
+                    SingleOrVec::Single(s) => {
+                        format!(r#""{:?}""#, s)
+                    }
+                    SingleOrVec::Vec(v) => {
+                        let mut s = "one of {".into();
+
+                        for (i, t) in v.iter().enumerate() {
+                            s += format!(r#""{:?}""#, t).as_str();
+                            if i != v.len() - 1 {
+                                s += ", "
+                            }
+                        }
+                        s += "}";
+
+                        s
+                    }
+                },
+                actual
+            ),
+            ErrorValue::InvalidEnumValue { expected } => {
+                let enum_vals: Vec<String> = expected.iter().map(|v| v.to_string()).collect();
+                write!(
+                    f,
+                    "invalid enum value, expected to be one of {{{}}}",
+                    enum_vals.join(", ")
+                )
+            }
+            ErrorValue::InvalidConstValue { expected } => {
+                write!(f, "invalid value, expected {}", expected)
+            }
+            ErrorValue::NotMultipleOf { multiple_of } => {
+                write!(f, "the value is expected to be multiple of {}", multiple_of)
+            }
+            ErrorValue::LessThanExpected { min, exclusive } => {
+                if *exclusive {
+                    write!(f, "the value is expected to be more than {}", min)
+                } else {
+                    write!(f, "the value is expected to be at least {}", min)
+                }
+            }
+            ErrorValue::MoreThanExpected { max, exclusive } => {
+                if *exclusive {
+                    write!(f, "the value is expected to be less than {}", max)
+                } else {
+                    write!(f, "the value is expected to be at most {}", max)
+                }
+            }
+            ErrorValue::NoPatternMatch { pattern } => {
+                write!(f, r#"the string must match the pattern "{}""#, pattern)
+            }
+            ErrorValue::TooLong { max_length } => write!(
+                f,
+                r#"the string must not be longer than {} characters"#,
+                max_length
+            ),
+            ErrorValue::TooShort { min_length } => write!(
+                f,
+                r#"the string must be at least {} characters long"#,
+                min_length
+            ),
+            ErrorValue::NoneValid {
+                exclusive: _,
+                schemas: _,
+                errors,
+            } => {
+                writeln!(f, r#"no subschema matched the value:"#)?;
+
+                for (i, e) in errors.iter().enumerate() {
+                    write!(f, "{}", e.localized(self))?;
+
+                    if i != errors.len() - 1 {
+                        writeln!(f, "\n")?;
+                    }
+                }
+
+                Ok(())
+            }
+            ErrorValue::MoreThanOneValid { schemas: _, matched } => write!(
+                f,
+                r#"expected exactly one schema to match, but {} schemas matched"#,
+                matched.len()
+            ),
+            ErrorValue::UnknownVariant {
+                property,
+                tag,
+                expected,
+            } => {
+                let tags: Vec<String> = expected.iter().map(|v| v.to_string()).collect();
+                write!(
+                    f,
+                    r#"unknown variant {} of "{}", expected to be one of {{{}}}"#,
+                    tag,
+                    property,
+                    tags.join(", ")
+                )
+            }
+            ErrorValue::ValidNot { matched } => {
+                if let Some(meta) = matched {
+                    if let Some(title) = &meta.title {
+                        return write!(f, r#"the value must not be a "{}""#, title);
+                    }
+                }
+
+                write!(f, r#"the value is disallowed by a "not" schema"#)
+            }
+            ErrorValue::NotUnique { first: _, duplicate: _ } => {
+                write!(f, r#"all items in the array must be unique"#)
+            }
+            ErrorValue::MustContain { schema } => {
+                if let Some(meta) = schema {
+                    if let Some(title) = &meta.title {
+                        return write!(
+                            f,
+                            r#"at least one of the items in the array must be "{}""#,
+                            title
+                        );
+                    }
+                }
+
+                write!(
+                    f,
+                    r#"at least one of the items in the array must match the given schema"#
+                )
+            }
+            ErrorValue::NotEnoughItems { min } => {
+                write!(f, "the array must have at least {} items", min)
+            }
+            ErrorValue::TooManyItems { max } => {
+                write!(f, "the array cannot have more than {} items", max)
+            }
+            ErrorValue::NotEnoughProperties { min } => {
+                write!(f, "the object must have at least {} properties", min)
+            }
+            ErrorValue::TooManyProperties { max } => {
+                write!(f, "the object cannot have more than {} properties", max)
+            }
+            ErrorValue::RequiredProperty { name } => {
+                write!(f, r#"the required property "{}" is missing"#, name)
+            }
+            ErrorValue::ForbiddenProperty { name } => {
+                write!(f, r#"the property "{}" is not allowed"#, name)
+            }
+            ErrorValue::ReadOnly => write!(f, "the property is read-only"),
+            ErrorValue::WriteOnly => write!(f, "the property is write-only"),
+            ErrorValue::Deprecated => write!(f, "the value is deprecated"),
+            ErrorValue::Keyword { keyword: _, message } => f.write_str(message),
+            ErrorValue::Custom(err) => err.fmt(f),
+        }
+    }
+
+    fn invalid_schema(&self, error: &InvalidSchema, f: &mut Formatter<'_>) -> fmt::Result {
+        match error {
+            InvalidSchema::MissingDefinition(s) => write!(f, r#"missing local definition "{}""#, s),
+            InvalidSchema::InvalidPattern { pattern, error } => {
+                write!(f, r#"invalid regex pattern "{}": {}"#, pattern, error)
+            }
+            InvalidSchema::ExternalReference(r) => write!(
+                f,
+                r#"the schema contains unresolved external reference: "{}""#,
+                r
+            ),
+            InvalidSchema::EmptyRange { min, max } => {
+                write!(f, r#""{}" is greater than "{}""#, min, max)
+            }
+            InvalidSchema::TypeMismatch { keyword, value } => {
+                write!(f, r#"the "{}" value {} does not match the type"#, keyword, value)
+            }
+            InvalidSchema::RequiredNotAllowed(name) => write!(
+                f,
+                r#"the required property "{}" is not allowed by "additionalProperties""#,
+                name
+            ),
+            InvalidSchema::UnreachableOneOf { index } => write!(
+                f,
+                r#"the "oneOf" subschema {} can never be the only one that matches"#,
+                index
+            ),
+            InvalidSchema::DuplicateOneOf { index, other } => write!(
+                f,
+                r#"the "oneOf" subschema {} is the same as the subschema {}"#,
+                index, other
+            ),
+            InvalidSchema::IgnoredKeyword(keyword) => {
+                write!(f, r#"the "{}" keyword has no effect for the type"#, keyword)
+            }
+            InvalidSchema::RefCycle(name) => write!(
+                f,
+                r#"the definition "{}" always contains itself, no value is valid"#,
+                name
+            ),
+        }
+    }
+
+    fn unsupported_value(&self, error: &UnsupportedValue, f: &mut Formatter<'_>) -> fmt::Result {
+        match error {
+            UnsupportedValue::KeyNotString => write!(f, "map key must be a string"),
+            UnsupportedValue::ValueWithoutKey => write!(f, "map value must follow a key"),
+        }
+    }
+}
+
+/// A value that is displayed with a given [Catalog](Catalog).
+///
+/// It is returned by the `localized` methods of the error types.
+pub struct Localized<'a, T: ?Sized, C> {
+    value: &'a T,
+    catalog: &'a C,
+    best_match: bool,
+}
+
+impl<'a, T: ?Sized, C: Catalog> Localized<'a, T, C> {
+    pub(super) fn new(value: &'a T, catalog: &'a C) -> Self {
+        Self {
+            value,
+            catalog,
+            best_match: false,
+        }
+    }
+}
+
+impl<'a, S: Span, C: Catalog> Localized<'a, Errors<S>, C> {
+    /// Only display the most relevant errors of `anyOf` and `oneOf`
+    /// failures, see [best_match](Errors::best_match).
+    pub fn best_match(mut self) -> Self {
+        self.best_match = true;
+        self
+    }
+}
+
+impl<'a, S: Span, C: Catalog> Display for Localized<'a, ErrorValue<S>, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.catalog.error_value(self.value, f)
+    }
+}
+
+impl<'a, S: Span, C: Catalog> Display for Localized<'a, Error<S>, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut start_paren = false;
+
+        if f.alternate() {
+            if let Some(span) = &self.value.span {
+                write!(f, "({:?}", span)?;
+                start_paren = true;
+            }
+
+            if let Some(meta) = &self.value.meta {
+                if let Some(title) = &meta.title {
+                    if start_paren {
+                        write!(f, r#", schema: "{}""#, title)?;
+                    } else {
+                        write!(f, r#"(schema: "{}""#, title)?;
+                    }
+                    start_paren = true;
+                }
+            }
+        }
+
+        if start_paren {
+            write!(f, ") ")?;
+        }
+
+        if f.alternate() && self.value.is_warning() {
+            write!(f, "warning: ")?;
+        }
+
+        match &self.value.message {
+            Some(message) => f.write_str(message),
+            None => self.catalog.error_value(&self.value.value, f),
+        }
+    }
+}
+
+impl<'a, S: Span, C: Catalog> Display for Localized<'a, Errors<S>, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.best_match {
+            return self.value.best_match().localized(self.catalog).fmt(f);
+        }
+
+        for e in self.value.iter() {
+            writeln!(f, "{}", e.localized(self.catalog))?;
+        }
+        Ok(())
+    }
+}
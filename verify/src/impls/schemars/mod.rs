@@ -1,9 +1,21 @@
 #[macro_use] mod macros;
+mod cache;
 mod schema;
+mod subschema;
+mod value;
 mod verify;
 
 pub mod errors;
+pub mod infer;
+pub mod keywords;
+pub mod messages;
+pub mod openapi;
+pub mod patch;
+pub mod rules;
 
+pub use cache::{cached_schema, cached_schema_with};
 pub use schema::*;
+pub use subschema::{Subschema, SubschemaExt};
 pub use errors::Errors;
+pub use verify::lint;
 
@@ -0,0 +1,461 @@
+//! Validation of request and response bodies with OpenAPI 3 documents.
+//!
+//! The schema of a body is selected by the operation, the status code
+//! for responses, and the media type:
+//!
+//! ```ignore
+//! use verify::schemars::openapi::OpenApi;
+//!
+//! let api = OpenApi::from_file("openapi.json")?;
+//!
+//! let body = api.request("POST", "/pets", "application/json")?;
+//! body.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//!
+//! let body = api.response("GET", "/pets/42", 200, "application/json")?;
+//! body.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//! ```
+//!
+//! References to `#/components/schemas` and `nullable` are supported,
+//! and `readOnly` and `writeOnly` properties are only allowed
+//! in the [Direction](super::Direction) they are meant for.
+//!
+//! Only JSON documents are supported.
+
+use super::{
+    keywords::{Keyword, Keywords},
+    schema::{verify_schema, Direction},
+    Errors,
+};
+use crate::{span::Span, Validate, Verifier};
+use schemars_crate::{schema::Schema, Map};
+use serde_json::Value;
+use std::{fmt, path::Path, sync::Arc};
+
+/// An error that occurred while loading a document,
+/// or while looking up a schema in it.
+#[derive(Debug)]
+pub enum OpenApiError {
+    /// The document could not be read.
+    Io(std::io::Error),
+    /// The document is not valid JSON.
+    Json(serde_json::Error),
+    /// The document is not an OpenAPI 3.0 or 3.1 document.
+    UnsupportedVersion(String),
+    /// The document or a schema in it is invalid.
+    InvalidDocument(String),
+    /// There is no such operation in the document.
+    OperationNotFound { method: String, path: String },
+    /// The operation has no request body.
+    NoRequestBody,
+    /// The operation has no response for the status code.
+    StatusNotFound(u16),
+    /// There is no content for the media type.
+    MediaTypeNotFound(String),
+}
+
+impl fmt::Display for OpenApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenApiError::Io(err) => write!(f, "failed to read the document: {}", err),
+            OpenApiError::Json(err) => write!(f, "invalid JSON document: {}", err),
+            OpenApiError::UnsupportedVersion(v) => {
+                write!(f, r#"unsupported OpenAPI version "{}""#, v)
+            }
+            OpenApiError::InvalidDocument(err) => write!(f, "invalid document: {}", err),
+            OpenApiError::OperationNotFound { method, path } => {
+                write!(f, "operation {} {} was not found", method, path)
+            }
+            OpenApiError::NoRequestBody => write!(f, "the operation has no request body"),
+            OpenApiError::StatusNotFound(status) => {
+                write!(f, "the operation has no response for status {}", status)
+            }
+            OpenApiError::MediaTypeNotFound(media_type) => {
+                write!(f, r#"there is no content for media type "{}""#, media_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OpenApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OpenApiError::Io(err) => Some(err),
+            OpenApiError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// An OpenAPI 3.0 or 3.1 document.
+#[derive(Clone)]
+pub struct OpenApi {
+    document: Value,
+    definitions: Arc<Map<String, Schema>>,
+    keywords: Keywords,
+}
+
+impl fmt::Debug for OpenApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpenApi")
+            .field("document", &self.document)
+            .field("definitions", &self.definitions)
+            .field("keywords", &self.keywords.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl OpenApi {
+    /// Load a JSON document from a file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, OpenApiError> {
+        let content = std::fs::read(path).map_err(OpenApiError::Io)?;
+        Self::from_value(serde_json::from_slice(&content).map_err(OpenApiError::Json)?)
+    }
+
+    /// Load a document that was already parsed.
+    pub fn from_value(document: Value) -> Result<Self, OpenApiError> {
+        let version = document
+            .get("openapi")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        if !version.starts_with("3.0") && !version.starts_with("3.1") {
+            return Err(OpenApiError::UnsupportedVersion(version.to_string()));
+        }
+
+        let mut definitions = Map::new();
+
+        if let Some(schemas) = document
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+        {
+            for (name, schema) in schemas {
+                definitions.insert(name.clone(), parse_schema(schema, name)?);
+            }
+        }
+
+        Ok(Self {
+            document,
+            definitions: Arc::new(definitions),
+            keywords: Keywords::new(),
+        })
+    }
+
+    /// Register a handler for a custom keyword.
+    ///
+    /// The handler is used by all the bodies that are returned afterwards,
+    /// read more [here](super::keywords).
+    pub fn keyword<K: Keyword + 'static>(mut self, name: impl Into<String>, keyword: K) -> Self {
+        self.keywords.insert(name.into(), Arc::new(keyword));
+        self
+    }
+
+    /// The schema of the request body of an operation.
+    ///
+    /// The path is either a path of the document such as `/pets/{id}`,
+    /// or an actual path such as `/pets/42`.
+    pub fn request(
+        &self,
+        method: &str,
+        path: &str,
+        media_type: &str,
+    ) -> Result<Body, OpenApiError> {
+        let operation = self.operation(method, path)?;
+
+        let body = operation
+            .get("requestBody")
+            .ok_or(OpenApiError::NoRequestBody)?;
+
+        self.body(self.resolve(body)?, media_type, Direction::Request)
+    }
+
+    /// The schema of the response body of an operation.
+    ///
+    /// Responses for ranges of status codes such as `2XX`,
+    /// and the `default` response are also used if needed.
+    pub fn response(
+        &self,
+        method: &str,
+        path: &str,
+        status: u16,
+        media_type: &str,
+    ) -> Result<Body, OpenApiError> {
+        let operation = self.operation(method, path)?;
+
+        let responses = operation
+            .get("responses")
+            .and_then(Value::as_object)
+            .ok_or(OpenApiError::StatusNotFound(status))?;
+
+        let exact = status.to_string();
+        let range = format!("{}XX", status / 100);
+
+        let response = responses
+            .get(&exact)
+            .or_else(|| {
+                responses
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(&range))
+                    .map(|(_, v)| v)
+            })
+            .or_else(|| responses.get("default"))
+            .ok_or(OpenApiError::StatusNotFound(status))?;
+
+        self.body(self.resolve(response)?, media_type, Direction::Response)
+    }
+
+    /// The definitions from `components/schemas`.
+    pub fn definitions(&self) -> &Map<String, Schema> {
+        &self.definitions
+    }
+
+    fn operation(&self, method: &str, path: &str) -> Result<&Value, OpenApiError> {
+        let not_found = || OpenApiError::OperationNotFound {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+        };
+
+        let paths = self
+            .document
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(not_found)?;
+
+        let item = match paths.get(path) {
+            Some(item) => item,
+            // The template with the most literal segments wins.
+            None => paths
+                .iter()
+                .filter_map(|(template, item)| Some((path_matches(template, path)?, item)))
+                .max_by_key(|(literals, _)| *literals)
+                .map(|(_, item)| item)
+                .ok_or_else(not_found)?,
+        };
+
+        self.resolve(item)?
+            .get(method.to_lowercase())
+            .ok_or_else(not_found)
+    }
+
+    fn body(
+        &self,
+        body: &Value,
+        media_type: &str,
+        direction: Direction,
+    ) -> Result<Body, OpenApiError> {
+        let not_found = || OpenApiError::MediaTypeNotFound(media_type.to_string());
+
+        let content = body
+            .get("content")
+            .and_then(Value::as_object)
+            .ok_or_else(not_found)?;
+
+        let essence = media_type_essence(media_type);
+        let (main_type, _) = essence.split_once('/').unwrap_or((&essence, ""));
+
+        let media = content
+            .iter()
+            .find(|(k, _)| media_type_essence(k) == essence)
+            .or_else(|| {
+                content
+                    .iter()
+                    .find(|(k, _)| media_type_essence(k) == format!("{}/*", main_type))
+            })
+            .or_else(|| content.iter().find(|(k, _)| k.as_str() == "*/*"))
+            .map(|(_, v)| v)
+            .ok_or_else(not_found)?;
+
+        let schema = match media.get("schema") {
+            Some(s) => parse_schema(s, media_type)?,
+            None => Schema::Bool(true),
+        };
+
+        Ok(Body {
+            schema,
+            definitions: self.definitions.clone(),
+            keywords: self.keywords.clone(),
+            direction,
+        })
+    }
+
+    /// Follow the references of objects other than schemas,
+    /// such as responses and request bodies.
+    fn resolve<'v>(&'v self, mut value: &'v Value) -> Result<&'v Value, OpenApiError> {
+        // Limit the depth in case of cycles.
+        for _ in 0..32 {
+            let reference = match value.get("$ref").and_then(Value::as_str) {
+                Some(r) => r,
+                None => return Ok(value),
+            };
+
+            value = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.document.pointer(pointer))
+                .ok_or_else(|| {
+                    OpenApiError::InvalidDocument(format!(
+                        r#"unresolved reference "{}""#,
+                        reference
+                    ))
+                })?;
+        }
+
+        Err(OpenApiError::InvalidDocument(
+            "too many nested references".into(),
+        ))
+    }
+}
+
+/// The schema of a request or response body.
+#[derive(Clone)]
+pub struct Body {
+    schema: Schema,
+    definitions: Arc<Map<String, Schema>>,
+    keywords: Keywords,
+    direction: Direction,
+}
+
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Body")
+            .field("schema", &self.schema)
+            .field("definitions", &self.definitions)
+            .field("keywords", &self.keywords.keys().collect::<Vec<_>>())
+            .field("direction", &self.direction)
+            .finish()
+    }
+}
+
+impl Body {
+    /// The schema of the body.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Whether this is a request or a response body.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+impl<S: Span> Verifier<S> for Body {
+    type Error = Errors<S>;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        verify_schema(
+            &self.schema,
+            &self.definitions,
+            &self.keywords,
+            Some(self.direction),
+            value,
+            span,
+        )
+    }
+}
+
+fn parse_schema(schema: &Value, name: &str) -> Result<Schema, OpenApiError> {
+    let mut schema = schema.clone();
+    normalize(&mut schema);
+
+    serde_json::from_value(schema)
+        .map_err(|err| OpenApiError::InvalidDocument(format!(r#"schema "{}": {}"#, name, err)))
+}
+
+/// Convert the OpenAPI 3.0 boolean `exclusiveMinimum` and `exclusiveMaximum`
+/// to the numeric form.
+///
+/// Only subschemas are converted, values such as `enum` or `default` are left as they are.
+fn normalize(schema: &mut Value) {
+    let obj = match schema {
+        Value::Object(obj) => obj,
+        _ => return,
+    };
+
+    for (exclusive, inclusive) in &[
+        ("exclusiveMinimum", "minimum"),
+        ("exclusiveMaximum", "maximum"),
+    ] {
+        if let Some(Value::Bool(is_exclusive)) = obj.get(*exclusive) {
+            let is_exclusive = *is_exclusive;
+            obj.remove(*exclusive);
+
+            if is_exclusive {
+                if let Some(limit) = obj.remove(*inclusive) {
+                    obj.insert(exclusive.to_string(), limit);
+                }
+            }
+        }
+    }
+
+    for (keyword, value) in obj.iter_mut() {
+        match keyword.as_str() {
+            // A schema or an array of schemas.
+            "items"
+            | "additionalItems"
+            | "contains"
+            | "additionalProperties"
+            | "propertyNames"
+            | "not"
+            | "if"
+            | "then"
+            | "else"
+            | "allOf"
+            | "anyOf"
+            | "oneOf"
+            | "prefixItems" => match value {
+                Value::Array(schemas) => schemas.iter_mut().for_each(normalize),
+                schema => normalize(schema),
+            },
+            // Schemas by names.
+            "properties" | "patternProperties" | "dependencies" | "definitions" | "$defs" => {
+                if let Value::Object(schemas) = value {
+                    schemas.values_mut().for_each(normalize);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The number of literal segments if the path matches the template.
+fn path_matches(template: &str, path: &str) -> Option<usize> {
+    let template = template.trim_end_matches('/').split('/');
+    let mut path = path.trim_end_matches('/').split('/');
+
+    let mut literals = 0;
+
+    for t in template {
+        let p = path.next()?;
+
+        if t.starts_with('{') && t.ends_with('}') {
+            if p.is_empty() {
+                return None;
+            }
+        } else if t == p {
+            literals += 1;
+        } else {
+            return None;
+        }
+    }
+
+    match path.next() {
+        Some(_) => None,
+        None => Some(literals),
+    }
+}
+
+/// The media type without parameters, in lowercase.
+fn media_type_essence(media_type: &str) -> String {
+    media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
@@ -0,0 +1,574 @@
+//! Validation of JSON Patch ([RFC 6902](https://tools.ietf.org/html/rfc6902))
+//! and JSON Merge Patch ([RFC 7396](https://tools.ietf.org/html/rfc7396)) documents.
+//!
+//! A patch is applied to the current document, and the result
+//! is validated against the schema:
+//!
+//! ```ignore
+//! use verify::schemars::patch;
+//!
+//! let patch: Vec<patch::Operation> = serde_json::from_value(body)?;
+//! let updated = patch::verify_patch(&SchemaVerifier::new(&root), &current, &patch)?;
+//! ```
+//!
+//! Only the errors at or below the paths that were touched by the patch are returned,
+//! and the errors of their parents that are about a touched property (such as a removed
+//! required property). Errors in other parts of the document were not caused by the patch.
+//!
+//! Operations with paths that are not allowed by the schema are reported as
+//! [UnknownProperty](super::errors::ErrorValue::UnknownProperty) errors,
+//! and operations that cannot be applied as [Custom](super::errors::ErrorValue::Custom) errors.
+
+use super::{
+    cache::cached_regex,
+    errors::{Error, ErrorValue, Errors},
+    schema::local_definition,
+    value::json_eq,
+    SchemaVerifier,
+};
+use crate::{
+    serde::{NewSpan, PathSpans, Spanned, Spans},
+    span::{Path, Segment},
+    Verifier,
+};
+use schemars_crate::schema::{RootSchema, Schema, SingleOrVec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// A JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    /// Add a property or insert an array item.
+    Add { path: String, value: Value },
+    /// Remove a value.
+    Remove { path: String },
+    /// Replace an existing value.
+    Replace { path: String, value: Value },
+    /// Remove a value and add it at another path.
+    Move { from: String, path: String },
+    /// Add a copy of a value at another path.
+    Copy { from: String, path: String },
+    /// Check that the value at the path is equal to the given one.
+    Test { path: String, value: Value },
+}
+
+impl Operation {
+    /// The target path of the operation.
+    pub fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Move { path, .. }
+            | Operation::Copy { path, .. }
+            | Operation::Test { path, .. } => path,
+        }
+    }
+
+    /// The paths that are changed by the operation.
+    fn changed(&self) -> Vec<&str> {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Copy { path, .. } => vec![path],
+            Operation::Move { from, path } => vec![from, path],
+            Operation::Test { .. } => Vec::new(),
+        }
+    }
+}
+
+/// An error that occurred while applying a JSON Patch.
+///
+/// The index is the index of the operation in the patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The path is not a valid JSON Pointer.
+    InvalidPointer { index: usize, pointer: String },
+    /// There is no value at the path.
+    NotFound { index: usize, pointer: String },
+    /// A test operation failed.
+    TestFailed { index: usize, pointer: String },
+    /// A value cannot be moved into one of its children.
+    MoveIntoChild { index: usize, pointer: String },
+}
+
+impl PatchError {
+    /// The index of the operation that failed.
+    pub fn index(&self) -> usize {
+        match self {
+            PatchError::InvalidPointer { index, .. }
+            | PatchError::NotFound { index, .. }
+            | PatchError::TestFailed { index, .. }
+            | PatchError::MoveIntoChild { index, .. } => *index,
+        }
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidPointer { index, pointer } => {
+                write!(f, r#"operation {}: invalid pointer "{}""#, index, pointer)
+            }
+            PatchError::NotFound { index, pointer } => {
+                write!(
+                    f,
+                    r#"operation {}: the path "{}" does not exist"#,
+                    index, pointer
+                )
+            }
+            PatchError::TestFailed { index, pointer } => {
+                write!(
+                    f,
+                    r#"operation {}: the test at "{}" failed"#,
+                    index, pointer
+                )
+            }
+            PatchError::MoveIntoChild { index, pointer } => write!(
+                f,
+                r#"operation {}: cannot move a value into its child "{}""#,
+                index, pointer
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Apply a JSON Patch to the document.
+///
+/// Either all the operations are applied, or none of them.
+pub fn apply(document: &Value, patch: &[Operation]) -> Result<Value, PatchError> {
+    let mut document = document.clone();
+
+    for (index, op) in patch.iter().enumerate() {
+        apply_operation(&mut document, index, op)?;
+    }
+
+    Ok(document)
+}
+
+/// Apply a JSON Merge Patch to the document.
+pub fn merge(document: &Value, patch: &Value) -> Value {
+    let mut document = document.clone();
+    merge_into(&mut document, patch);
+    document
+}
+
+/// Return the indices of the operations that change paths
+/// that are not allowed by the schema.
+///
+/// Only properties that are denied by `additionalProperties` are found,
+/// paths under `anyOf`, `oneOf` and the other subschemas are always allowed.
+pub fn disallowed(root: &RootSchema, patch: &[Operation]) -> Vec<usize> {
+    patch
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| {
+            op.changed().into_iter().any(|p| match tokens(p) {
+                Some(tokens) => !allowed(root, &tokens),
+                None => false,
+            })
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Apply a JSON Patch to the document, and validate the result.
+///
+/// The patched document is returned if it is valid, read more [here](self).
+#[allow(clippy::result_large_err)]
+pub fn verify_patch(
+    verifier: &SchemaVerifier<'_>,
+    document: &Value,
+    patch: &[Operation],
+) -> Result<Value, Errors<Path>> {
+    let disallowed = disallowed(verifier.root(), patch);
+
+    if !disallowed.is_empty() {
+        return Err(Errors(
+            disallowed
+                .into_iter()
+                .map(|i| {
+                    Error::new(
+                        None,
+                        Some(to_path(patch[i].path())),
+                        ErrorValue::UnknownProperty,
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    let patched = apply(document, patch).map_err(|err| {
+        Errors::one(Error::new(
+            None,
+            Some(to_path(patch[err.index()].path())),
+            ErrorValue::Custom(err.to_string()),
+        ))
+    })?;
+
+    let touched = patch
+        .iter()
+        .flat_map(|op| op.changed())
+        .map(|p| {
+            let mut path = to_path(p);
+            // Appended items are touched at the array.
+            if p.ends_with("/-") {
+                path.pop();
+            }
+            path
+        })
+        .collect::<Vec<_>>();
+
+    verify_touched(verifier, patched, &touched)
+}
+
+/// Apply a JSON Merge Patch to the document, and validate the result.
+///
+/// The patched document is returned if it is valid, read more [here](self).
+#[allow(clippy::result_large_err)]
+pub fn verify_merge_patch(
+    verifier: &SchemaVerifier<'_>,
+    document: &Value,
+    patch: &Value,
+) -> Result<Value, Errors<Path>> {
+    let mut touched = Vec::new();
+    merge_touched(Some(document), patch, Path::new(), &mut touched);
+
+    verify_touched(verifier, merge(document, patch), &touched)
+}
+
+#[allow(clippy::result_large_err)]
+fn verify_touched(
+    verifier: &SchemaVerifier<'_>,
+    patched: Value,
+    touched: &[Path],
+) -> Result<Value, Errors<Path>> {
+    let errors = match verifier.verify_value(&Spanned::new(&patched, ContainerSpans::default())) {
+        Ok(()) => return Ok(patched),
+        Err(errors) => errors,
+    };
+
+    // Errors of the parents are only returned if they are about a touched property.
+    let errors: Errors<Path> = Errors(
+        errors
+            .into_iter()
+            .filter(|e| {
+                let span = e.span.clone().unwrap_or_default();
+                let property = match &e.value {
+                    ErrorValue::RequiredProperty { name }
+                    | ErrorValue::ForbiddenProperty { name } => Some(span.clone() + name.as_str()),
+                    _ => None,
+                };
+
+                touched
+                    .iter()
+                    .any(|t| contains(t, &span) || matches!(&property, Some(p) if contains(p, t)))
+            })
+            .collect(),
+    );
+
+    if errors.is_empty() {
+        Ok(patched)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The same as [PathSpans], except that the errors of maps and sequences themselves
+/// (e.g. a missing required property) have the path of the map or sequence
+/// instead of no span, so that they can be matched with the touched paths.
+#[derive(Default, Clone)]
+struct ContainerSpans(PathSpans);
+
+impl Spans for ContainerSpans {
+    type Span = Path;
+
+    fn key<S: ?Sized + Serialize>(&mut self, key: &S) -> NewSpan<Self::Span> {
+        self.0.key(key)
+    }
+
+    fn value<S: ?Sized + Serialize>(&mut self, value: &S) -> NewSpan<Self::Span> {
+        self.0.value(value)
+    }
+
+    fn unit(&mut self) -> NewSpan<Self::Span> {
+        self.0.unit()
+    }
+
+    fn map_start(&mut self) -> NewSpan<Self::Span> {
+        self.0.map_start()
+    }
+
+    fn map_end(&mut self) -> NewSpan<Self::Span> {
+        self.0.map_end();
+        NewSpan::Add(Some(Path::new()))
+    }
+
+    fn seq_start(&mut self) -> NewSpan<Self::Span> {
+        self.0.seq_start()
+    }
+
+    fn seq_end(&mut self) -> NewSpan<Self::Span> {
+        self.0.seq_end();
+        NewSpan::Add(Some(Path::new()))
+    }
+
+    fn descend(&self) -> Self {
+        Self(self.0.descend())
+    }
+}
+
+fn merge_into(document: &mut Value, patch: &Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        _ => {
+            *document = patch.clone();
+            return;
+        }
+    };
+
+    if !document.is_object() {
+        *document = Value::Object(Default::default());
+    }
+
+    let obj = document.as_object_mut().unwrap();
+
+    for (key, value) in patch {
+        if value.is_null() {
+            obj.remove(key);
+        } else {
+            merge_into(obj.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+fn merge_touched(document: Option<&Value>, patch: &Value, path: Path, touched: &mut Vec<Path>) {
+    match patch {
+        // An empty object only changes values that are not objects.
+        Value::Object(obj) if obj.is_empty() => {
+            if !matches!(document, Some(Value::Object(_))) {
+                touched.push(path);
+            }
+        }
+        Value::Object(obj) => {
+            for (key, value) in obj {
+                merge_touched(
+                    document.and_then(|d| d.get(key)),
+                    value,
+                    path.clone() + key.as_str(),
+                    touched,
+                );
+            }
+        }
+        _ => touched.push(path),
+    }
+}
+
+fn apply_operation(document: &mut Value, index: usize, op: &Operation) -> Result<(), PatchError> {
+    let parse = |pointer: &str| {
+        tokens(pointer).ok_or_else(|| PatchError::InvalidPointer {
+            index,
+            pointer: pointer.to_string(),
+        })
+    };
+
+    let not_found = |pointer: &str| PatchError::NotFound {
+        index,
+        pointer: pointer.to_string(),
+    };
+
+    match op {
+        Operation::Add { path, value } => {
+            add(document, &parse(path)?, value.clone()).ok_or_else(|| not_found(path))
+        }
+        Operation::Remove { path } => remove(document, &parse(path)?)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path)),
+        Operation::Replace { path, value } => {
+            let target = document.pointer_mut(path).ok_or_else(|| not_found(path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            let (from_tokens, path_tokens) = (parse(from)?, parse(path)?);
+
+            if path_tokens.len() > from_tokens.len() && path_tokens.starts_with(&from_tokens) {
+                return Err(PatchError::MoveIntoChild {
+                    index,
+                    pointer: path.clone(),
+                });
+            }
+
+            let value = remove(document, &from_tokens).ok_or_else(|| not_found(from))?;
+            add(document, &path_tokens, value).ok_or_else(|| not_found(path))
+        }
+        Operation::Copy { from, path } => {
+            let path_tokens = parse(path)?;
+            let value = document
+                .pointer(from)
+                .cloned()
+                .ok_or_else(|| not_found(from))?;
+            add(document, &path_tokens, value).ok_or_else(|| not_found(path))
+        }
+        Operation::Test { path, value } => match document.pointer(path) {
+            Some(v) if json_eq(v, value) => Ok(()),
+            Some(_) => Err(PatchError::TestFailed {
+                index,
+                pointer: path.clone(),
+            }),
+            None => Err(not_found(path)),
+        },
+    }
+}
+
+/// The unescaped tokens of a JSON Pointer.
+fn tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+
+    Path::from_json_pointer(pointer).ok()?;
+
+    Some(
+        pointer[1..]
+            .split('/')
+            .map(|t| t.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+fn to_path(pointer: &str) -> Path {
+    Path::from_json_pointer(pointer).unwrap_or_default()
+}
+
+fn parent<'v>(document: &'v mut Value, tokens: &[String]) -> Option<&'v mut Value> {
+    tokens
+        .iter()
+        .try_fold(document, |value, token| match value {
+            Value::Object(obj) => obj.get_mut(token),
+            Value::Array(arr) => arr.get_mut(array_index(token)?),
+            _ => None,
+        })
+}
+
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Option<()> {
+    let (last, tokens) = match tokens.split_last() {
+        Some(t) => t,
+        None => {
+            *document = value;
+            return Some(());
+        }
+    };
+
+    match parent(document, tokens)? {
+        Value::Object(obj) => {
+            obj.insert(last.clone(), value);
+        }
+        Value::Array(arr) if last == "-" => arr.push(value),
+        Value::Array(arr) => {
+            let i = array_index(last).filter(|i| *i <= arr.len())?;
+            arr.insert(i, value);
+        }
+        _ => return None,
+    }
+
+    Some(())
+}
+
+fn remove(document: &mut Value, tokens: &[String]) -> Option<Value> {
+    let (last, tokens) = tokens.split_last()?;
+
+    match parent(document, tokens)? {
+        Value::Object(obj) => obj.remove(last),
+        Value::Array(arr) => {
+            let i = array_index(last).filter(|i| *i < arr.len())?;
+            Some(arr.remove(i))
+        }
+        _ => None,
+    }
+}
+
+/// Array indices must not have leading zeros.
+fn array_index(token: &str) -> Option<usize> {
+    match Path::from_json_pointer(&format!("/{}", token))
+        .ok()?
+        .iter()
+        .next()?
+    {
+        Segment::Index(i) => Some(*i),
+        Segment::Key(_) => None,
+    }
+}
+
+/// Whether the path is the parent path or below it.
+fn contains(parent: &Path, path: &Path) -> bool {
+    parent.len() <= path.len()
+        && parent.iter().zip(path.iter()).all(|(a, b)| match (a, b) {
+            (Segment::Key(a), Segment::Key(b)) => a == b,
+            (Segment::Index(a), Segment::Index(b)) => a == b,
+            // Indices parsed from pointers can also be keys.
+            (Segment::Key(k), Segment::Index(i)) | (Segment::Index(i), Segment::Key(k)) => {
+                *k == i.to_string()
+            }
+        })
+}
+
+/// Whether the schema allows the path.
+fn allowed(root: &RootSchema, tokens: &[String]) -> bool {
+    let mut schema = &root.schema;
+
+    for token in tokens {
+        // Follow the references, with a limit in case of cycles.
+        for _ in 0..32 {
+            match schema
+                .reference
+                .as_deref()
+                .and_then(local_definition)
+                .and_then(|name| root.definitions.get(name))
+            {
+                Some(Schema::Object(s)) => schema = s,
+                Some(Schema::Bool(allow)) => return *allow,
+                None => break,
+            }
+        }
+
+        let next = match (&schema.object, &schema.array) {
+            (_, Some(arr)) if token == "-" || array_index(token).is_some() => match &arr.items {
+                Some(SingleOrVec::Single(item)) => Some(&**item),
+                Some(SingleOrVec::Vec(items)) => array_index(token).and_then(|i| items.get(i)),
+                None => None,
+            },
+            (Some(obj), _) => match obj.properties.get(token).or_else(|| {
+                obj.pattern_properties
+                    .iter()
+                    .find(|(pattern, _)| {
+                        cached_regex(pattern)
+                            .map(|r| r.is_match(token))
+                            .unwrap_or(false)
+                    })
+                    .map(|(_, s)| s)
+            }) {
+                Some(s) => Some(s),
+                None => match obj.additional_properties.as_deref() {
+                    Some(Schema::Bool(false)) => return false,
+                    other => other,
+                },
+            },
+            _ => None,
+        };
+
+        schema = match next {
+            Some(Schema::Object(s)) => s,
+            Some(Schema::Bool(allow)) => return *allow,
+            // Nothing is known about the rest of the path.
+            None => return true,
+        };
+    }
+
+    true
+}
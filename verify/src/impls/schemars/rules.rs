@@ -0,0 +1,330 @@
+//! Typed rules that can be used instead of writing schemas by hand.
+//!
+//! The rules are built into schemas internally, so they are validated
+//! the same way, and return the same [Errors](super::Errors):
+//!
+//! ```ignore
+//! use verify::schemars::rules;
+//!
+//! let config = rules::object()
+//!     .field("host", rules::string().len(1..))
+//!     .field("port", rules::int().range(1..=65535))
+//!     .optional("tags", rules::array(rules::string()).unique())
+//!     .deny_unknown();
+//!
+//! config.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+//!
+//! // The rules can also be exported as a schema.
+//! let schema = config.root_schema();
+//! ```
+//!
+//! Custom keywords are not validated by the rules,
+//! the exported schema can be used with a [SchemaVerifier](super::SchemaVerifier) for them.
+
+use super::{keywords::Keywords, schema::verify_schema, Errors};
+use crate::{span::Span, Validate, Verifier};
+use schemars_crate::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use std::ops::{Bound, RangeBounds};
+
+/// Implemented by all the rules.
+pub trait Rule {
+    /// Build the schema for the rule.
+    fn into_schema(self) -> SchemaObject;
+}
+
+impl Rule for SchemaObject {
+    fn into_schema(self) -> SchemaObject {
+        self
+    }
+}
+
+/// Implements everything that is common for all the rules.
+macro_rules! rule {
+    ($name:ident) => {
+        impl $name {
+            /// Set the title of the schema.
+            pub fn title(mut self, title: impl Into<String>) -> Self {
+                self.0.metadata().title = Some(title.into());
+                self
+            }
+
+            /// Set the description of the schema.
+            pub fn description(mut self, description: impl Into<String>) -> Self {
+                self.0.metadata().description = Some(description.into());
+                self
+            }
+
+            /// Export the rule as a schema.
+            pub fn root_schema(&self) -> RootSchema {
+                RootSchema {
+                    meta_schema: Some("http://json-schema.org/draft-07/schema#".into()),
+                    schema: self.0.clone(),
+                    definitions: Default::default(),
+                }
+            }
+        }
+
+        impl Rule for $name {
+            fn into_schema(self) -> SchemaObject {
+                self.0
+            }
+        }
+
+        impl<S: Span> Verifier<S> for $name {
+            type Error = Errors<S>;
+
+            fn verify_value<V: ?Sized + Validate<Span = S>>(
+                &self,
+                value: &V,
+            ) -> Result<(), Self::Error> {
+                self.verify_value_with_span(value, None)
+            }
+
+            fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+                &self,
+                value: &V,
+                span: Option<V::Span>,
+            ) -> Result<(), Self::Error> {
+                verify_schema(
+                    &self.0,
+                    &Default::default(),
+                    &Keywords::new(),
+                    None,
+                    value,
+                    span,
+                )
+            }
+        }
+    };
+}
+
+/// Rule for objects.
+#[derive(Debug, Clone)]
+pub struct Object(SchemaObject);
+
+rule!(Object);
+
+impl Object {
+    /// Add a required field.
+    pub fn field(mut self, name: impl Into<String>, rule: impl Rule) -> Self {
+        let name = name.into();
+        self.0.object().required.insert(name.clone());
+        self.optional(name, rule)
+    }
+
+    /// Add a field that can be missing.
+    pub fn optional(mut self, name: impl Into<String>, rule: impl Rule) -> Self {
+        self.0
+            .object()
+            .properties
+            .insert(name.into(), rule.into_schema().into());
+        self
+    }
+
+    /// Do not allow fields that were not added.
+    pub fn deny_unknown(mut self) -> Self {
+        self.0.object().additional_properties = Some(Box::new(Schema::Bool(false)));
+        self
+    }
+}
+
+/// Rule for integers.
+#[derive(Debug, Clone)]
+pub struct Int(SchemaObject);
+
+rule!(Int);
+
+impl Int {
+    /// The integer must be in the range.
+    pub fn range(mut self, range: impl RangeBounds<i64>) -> Self {
+        let n = self.0.number();
+
+        match range.start_bound() {
+            Bound::Included(min) => n.minimum = Some(*min as f64),
+            Bound::Excluded(min) => n.exclusive_minimum = Some(*min as f64),
+            Bound::Unbounded => {}
+        }
+
+        match range.end_bound() {
+            Bound::Included(max) => n.maximum = Some(*max as f64),
+            Bound::Excluded(max) => n.exclusive_maximum = Some(*max as f64),
+            Bound::Unbounded => {}
+        }
+
+        self
+    }
+
+    /// The integer must be a multiple of the value.
+    pub fn multiple_of(mut self, value: i64) -> Self {
+        self.0.number().multiple_of = Some(value as f64);
+        self
+    }
+}
+
+/// Rule for numbers, integers are also accepted.
+#[derive(Debug, Clone)]
+pub struct Number(SchemaObject);
+
+rule!(Number);
+
+impl Number {
+    /// The number must be in the range.
+    pub fn range(mut self, range: impl RangeBounds<f64>) -> Self {
+        let n = self.0.number();
+
+        match range.start_bound() {
+            Bound::Included(min) => n.minimum = Some(*min),
+            Bound::Excluded(min) => n.exclusive_minimum = Some(*min),
+            Bound::Unbounded => {}
+        }
+
+        match range.end_bound() {
+            Bound::Included(max) => n.maximum = Some(*max),
+            Bound::Excluded(max) => n.exclusive_maximum = Some(*max),
+            Bound::Unbounded => {}
+        }
+
+        self
+    }
+
+    /// The number must be a multiple of the value.
+    pub fn multiple_of(mut self, value: f64) -> Self {
+        self.0.number().multiple_of = Some(value);
+        self
+    }
+}
+
+/// Rule for strings.
+#[derive(Debug, Clone)]
+pub struct Str(SchemaObject);
+
+rule!(Str);
+
+impl Str {
+    /// The length of the string in characters must be in the range.
+    pub fn len(mut self, range: impl RangeBounds<u32>) -> Self {
+        let (min, max) = length(range);
+        let s = self.0.string();
+        s.min_length = min;
+        s.max_length = max;
+        self
+    }
+
+    /// The string must match the regex pattern.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.0.string().pattern = Some(pattern.into());
+        self
+    }
+
+    /// The string must be one of the values.
+    pub fn values<I>(mut self, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.0.enum_values = Some(
+            values
+                .into_iter()
+                .map(|v| serde_json::Value::String(v.into()))
+                .collect(),
+        );
+        self
+    }
+}
+
+/// Rule for booleans.
+#[derive(Debug, Clone)]
+pub struct Bool(SchemaObject);
+
+rule!(Bool);
+
+/// Rule for arrays.
+#[derive(Debug, Clone)]
+pub struct Array(SchemaObject);
+
+rule!(Array);
+
+impl Array {
+    /// The count of items must be in the range.
+    pub fn len(mut self, range: impl RangeBounds<u32>) -> Self {
+        let (min, max) = length(range);
+        let a = self.0.array();
+        a.min_items = min;
+        a.max_items = max;
+        self
+    }
+
+    /// The items must be unique.
+    pub fn unique(mut self) -> Self {
+        self.0.array().unique_items = Some(true);
+        self
+    }
+}
+
+/// Rule that allows any value.
+#[derive(Debug, Clone)]
+pub struct Any(SchemaObject);
+
+rule!(Any);
+
+/// Create a rule for objects.
+///
+/// Unknown fields are allowed by default.
+pub fn object() -> Object {
+    Object(typed(InstanceType::Object))
+}
+
+/// Create a rule for integers.
+pub fn int() -> Int {
+    Int(typed(InstanceType::Integer))
+}
+
+/// Create a rule for numbers.
+pub fn number() -> Number {
+    Number(typed(InstanceType::Number))
+}
+
+/// Create a rule for strings.
+pub fn string() -> Str {
+    Str(typed(InstanceType::String))
+}
+
+/// Create a rule for booleans.
+pub fn boolean() -> Bool {
+    Bool(typed(InstanceType::Boolean))
+}
+
+/// Create a rule for arrays, every item must be valid for the given rule.
+pub fn array(items: impl Rule) -> Array {
+    let mut schema = typed(InstanceType::Array);
+    schema.array().items = Some(SingleOrVec::Single(Box::new(items.into_schema().into())));
+    Array(schema)
+}
+
+/// Create a rule that allows any value.
+pub fn any() -> Any {
+    Any(SchemaObject::default())
+}
+
+fn typed(instance_type: InstanceType) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(instance_type.into()),
+        ..Default::default()
+    }
+}
+
+fn length(range: impl RangeBounds<u32>) -> (Option<u32>, Option<u32>) {
+    let min = match range.start_bound() {
+        Bound::Included(min) => Some(*min),
+        Bound::Excluded(min) => Some(min.saturating_add(1)),
+        Bound::Unbounded => None,
+    };
+
+    let max = match range.end_bound() {
+        Bound::Included(max) => Some(*max),
+        Bound::Excluded(max) => Some(max.saturating_sub(1)),
+        Bound::Unbounded => None,
+    };
+
+    (min, max)
+}
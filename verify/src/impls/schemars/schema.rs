@@ -1,6 +1,7 @@
 //! Implementation of Verify, Verifier and Validator for schemas.
 
 use crate::{
+    adhoc::{peek, Peek},
     span::{Span, SpanExt},
     Validate, ValidateMap, ValidateSeq, Validator, Verifier,
 };
@@ -8,12 +9,22 @@ use schemars_crate::{
     schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec},
     Map, Set,
 };
+use serde_json::Value;
 use std::{
+    cell::RefCell,
     collections::{hash_map::DefaultHasher, HashMap},
     hash::{Hash, Hasher},
+    rc::Rc,
+    sync::Arc,
 };
 
-use super::errors::{Error, ErrorValue, Errors, ErrorsInner, InvalidSchema, UnsupportedValue};
+use super::{
+    cache::cached_regex,
+    errors::{Error, ErrorValue, Errors, ErrorsInner, InvalidSchema, Severity, UnsupportedValue},
+    keywords::{Keyword, Keywords},
+    subschema::{Subschema, SubschemaExt},
+    value::{json_eq, json_hash, to_json, to_json_property, to_json_scalar},
+};
 
 impl<S: Span> Verifier<S> for RootSchema {
     type Error = Errors<S>;
@@ -27,15 +38,258 @@ impl<S: Span> Verifier<S> for RootSchema {
         value: &V,
         span: Option<V::Span>,
     ) -> Result<(), Self::Error> {
-        SchemaValidator::new(&self.definitions, (&self.schema).into())
+        SchemaVerifier::new(self).verify_value_with_span(value, span)
+    }
+}
+
+/// The schema is validated without definitions,
+/// so it must not contain local references.
+///
+/// Custom keywords are ignored, use [SchemaVerifier](SchemaVerifier)
+/// or a [Subschema](super::Subschema) to register them.
+impl<S: Span> Verifier<S> for Schema {
+    type Error = Errors<S>;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        verify_schema(
+            self,
+            &Default::default(),
+            &Keywords::new(),
+            None,
+            value,
+            span,
+        )
+    }
+}
+
+/// The schema is validated without definitions,
+/// so it must not contain local references.
+///
+/// Custom keywords are ignored, use [SchemaVerifier](SchemaVerifier)
+/// or a [Subschema](super::Subschema) to register them.
+impl<S: Span> Verifier<S> for SchemaObject {
+    type Error = Errors<S>;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        verify_schema(
+            self,
+            &Default::default(),
+            &Keywords::new(),
+            None,
+            value,
+            span,
+        )
+    }
+}
+
+/// The direction of the data for OpenAPI schemas.
+///
+/// `readOnly` values are not allowed in requests, and `writeOnly` values
+/// are not allowed in responses. This also means that they are only required
+/// in the other direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The value is sent to the server.
+    Request,
+    /// The value is returned by the server.
+    Response,
+}
+
+/// A [Verifier](crate::Verifier) for a [RootSchema](RootSchema) with additional options.
+///
+/// [RootSchema](RootSchema) itself is also a verifier with the default options.
+#[derive(Clone)]
+pub struct SchemaVerifier<'s> {
+    root: &'s RootSchema,
+    keywords: Keywords,
+    partial: bool,
+    direction: Option<Direction>,
+}
+
+impl<'s> SchemaVerifier<'s> {
+    /// Create a verifier with the default options.
+    pub fn new(root: &'s RootSchema) -> Self {
+        Self {
+            root,
+            keywords: Keywords::new(),
+            partial: false,
+            direction: None,
+        }
+    }
+
+    /// Register a handler for a custom keyword.
+    ///
+    /// The handler is called for every schema that contains the keyword,
+    /// read more [here](super::keywords).
+    pub fn keyword<K: Keyword + 'static>(mut self, name: impl Into<String>, keyword: K) -> Self {
+        self.keywords.insert(name.into(), Arc::new(keyword));
+        self
+    }
+
+    /// Ignore the `required` keyword in all the schemas.
+    ///
+    /// It is useful for validating partial updates,
+    /// where only the changed properties are present.
+    pub fn partial(mut self, partial: bool) -> Self {
+        self.partial = partial;
+        self
+    }
+
+    /// Set the direction of the data, read more [here](Direction).
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Return the schema at the pointer with the custom keywords
+    /// of this verifier, read more [here](super::SubschemaExt).
+    pub fn subschema(&self, pointer: &str) -> Option<Subschema<'s>> {
+        self.root
+            .subschema(pointer)
+            .map(|s| s.with_keywords(self.keywords.clone()))
+    }
+
+    pub(super) fn root(&self) -> &'s RootSchema {
+        self.root
+    }
+
+    /// Validate a value and also return the warnings,
+    /// such as deprecated values.
+    ///
+    /// The value is valid if there are only warnings, they are returned in [Ok](Result::Ok).
+    /// Otherwise the warnings are returned after the errors.
+    #[allow(clippy::result_large_err)]
+    pub fn verify_with_warnings<S: Span, V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+    ) -> Result<Errors<S>, Errors<S>> {
+        let ctx = self.context();
+
+        let result = SchemaValidator::new(&ctx, (&self.root.schema).into()).validate_inner(value);
+        let warnings = ctx.warnings.into_inner();
+
+        match result {
+            Ok(()) => Ok(Errors(warnings)),
+            Err(mut errors) => {
+                errors.0.extend(warnings);
+                Err(errors)
+            }
+        }
+    }
+
+    fn context<S: Span>(&self) -> Context<'_, S> {
+        Context {
+            defs: &self.root.definitions,
+            keywords: &self.keywords,
+            partial: self.partial,
+            direction: self.direction,
+            warnings: Default::default(),
+            discriminators: Default::default(),
+        }
+    }
+}
+
+impl<'s, S: Span> Verifier<S> for SchemaVerifier<'s> {
+    type Error = Errors<S>;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        let ctx = self.context();
+
+        SchemaValidator::new(&ctx, (&self.root.schema).into())
             .with_parent_span(span)
             .validate_inner(value)
     }
 }
 
+/// Validate a value against a schema with the given definitions and custom keywords,
+/// the schema is either a [Schema](Schema) or a [SchemaObject](SchemaObject).
+pub(super) fn verify_schema<'s, S: Span, V: ?Sized + Validate<Span = S>>(
+    schema: impl Into<SchemaRef<'s>>,
+    defs: &Map<String, Schema>,
+    keywords: &Keywords,
+    direction: Option<Direction>,
+    value: &V,
+    span: Option<S>,
+) -> Result<(), Errors<S>> {
+    let ctx = Context {
+        defs,
+        keywords,
+        partial: false,
+        direction,
+        warnings: Default::default(),
+        discriminators: Default::default(),
+    };
+
+    SchemaValidator::new(&ctx, schema.into())
+        .with_parent_span(span)
+        .validate_inner(value)
+}
+
+/// Everything that is shared between the validators
+/// during a single validation.
+struct Context<'a, S: Span> {
+    defs: &'a Map<String, Schema>,
+    keywords: &'a Keywords,
+    partial: bool,
+    direction: Option<Direction>,
+    // Warnings do not fail the validation, so they are collected separately.
+    warnings: RefCell<ErrorsInner<S>>,
+    // The discriminators of the schemas with `oneOf` by their addresses,
+    // so that they are only looked up once for every item of a collection.
+    discriminators: RefCell<HashMap<*const SchemaObject, Option<Rc<Discriminator>>>>,
+}
+
+/// The discriminator property of `oneOf` subschemas,
+/// and the tag values with the index of the subschema they select.
+type Discriminator = (String, Vec<(Value, usize)>);
+
+impl<'a, S: Span> Context<'a, S> {
+    fn warn(&self, error: Error<S>) {
+        self.warnings.borrow_mut().push(error.warning());
+    }
+
+    /// The current count of warnings, see [discard](Context::discard).
+    fn mark(&self) -> usize {
+        self.warnings.borrow().len()
+    }
+
+    /// Remove the warnings that were added after the mark.
+    ///
+    /// Subschemas that are only tested (e.g. `if` or the failed branches
+    /// of `anyOf`) should not report warnings.
+    fn discard(&self, mark: usize) {
+        self.warnings.borrow_mut().truncate(mark);
+    }
+}
+
 /// This is technically not needed anymore,
 /// but should do no harm to leave it as is.
-enum SchemaRef<'s> {
+#[derive(Clone, Copy)]
+pub(super) enum SchemaRef<'s> {
     Bool(bool),
     Object(&'s SchemaObject),
 }
@@ -68,7 +322,7 @@ impl<'s> From<&'s RootSchema> for SchemaRef<'s> {
 /// must be used instead, it will validate the value against subschemas.
 struct SchemaValidator<'a, S: Span> {
     schema: SchemaRef<'a>,
-    defs: &'a Map<String, Schema>,
+    ctx: &'a Context<'a, S>,
 
     // If a schema was not found for an external tag,
     // everything should be allowed.
@@ -82,8 +336,9 @@ struct SchemaValidator<'a, S: Span> {
 
     // Array tracking
     arr_item_count: usize,
-    // For uniqueness checks
-    arr_hashes: HashMap<u64, Option<S>>,
+    // For uniqueness checks, the items are grouped by their hashes,
+    // and only compared to the items with the same hash.
+    arr_items: HashMap<u64, Vec<(Value, Option<S>)>>,
     arr_contains: Option<&'a Schema>,
 
     // Object tracking
@@ -94,16 +349,16 @@ struct SchemaValidator<'a, S: Span> {
 }
 
 impl<'a, S: Span> SchemaValidator<'a, S> {
-    fn new(defs: &'a Map<String, Schema>, schema: SchemaRef<'a>) -> Self {
+    fn new(ctx: &'a Context<'a, S>, schema: SchemaRef<'a>) -> Self {
         Self {
             schema,
-            defs,
+            ctx,
             parent_span: None,
             span: None,
             combined_span: None,
             tagged_allow: false,
             arr_item_count: 0,
-            arr_hashes: HashMap::new(),
+            arr_items: HashMap::new(),
             arr_contains: None,
             obj_required: Set::new(),
             obj_prop_count: 0,
@@ -121,17 +376,35 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
         let mut value_span = self.combined_span.clone();
         value_span.combine(value.span());
 
+        if matches!(&s.metadata, Some(meta) if meta.deprecated) {
+            self.ctx
+                .warn(Error::schema(s, value_span.clone(), ErrorValue::Deprecated));
+        }
+
+        // The metadata can be next to the reference, so it is checked first.
+        if let (Some(direction), Some(meta)) = (self.ctx.direction, &s.metadata) {
+            let error = match direction {
+                Direction::Request if meta.read_only => Some(ErrorValue::ReadOnly),
+                Direction::Response if meta.write_only => Some(ErrorValue::WriteOnly),
+                _ => None,
+            };
+
+            if let Some(error) = error {
+                return Err(Errors::one(Error::schema(s, value_span, error)));
+            }
+        }
+
         if let Some(r) = &s.reference {
             match local_definition(r) {
-                Some(local) => match self.defs.get(local) {
+                Some(local) => match self.ctx.defs.get(local) {
                     Some(s) => {
-                        return SchemaValidator::new(self.defs, s.into())
+                        return SchemaValidator::new(self.ctx, s.into())
                             .with_spans(self.parent_span.clone(), value.span())
                             .validate_inner(value)
                     }
                     None => {
-                        return Err(Errors::one(Error::new(
-                            s.metadata.clone(),
+                        return Err(Errors::one(Error::schema(
+                            s,
                             value_span.clone(),
                             ErrorValue::InvalidSchema(InvalidSchema::MissingDefinition(
                                 local.to_string(),
@@ -140,8 +413,8 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                     }
                 },
                 None => {
-                    return Err(Errors::one(Error::new(
-                        s.metadata.clone(),
+                    return Err(Errors::one(Error::schema(
+                        s,
                         value_span.clone(),
                         ErrorValue::InvalidSchema(InvalidSchema::ExternalReference(r.clone())),
                     )));
@@ -149,8 +422,24 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
             }
         }
 
+        // OpenAPI allows null for any schema with `nullable`.
+        if s.extensions.get("nullable") == Some(&Value::Bool(true))
+            && peek::<S, Errors<S>, V>(value)? == Peek::None
+        {
+            return Ok(());
+        }
+
         let mut errors = self.validate_subschemas(s, value).err();
 
+        if let Err(e) = self.validate_json(s, value) {
+            match &mut errors {
+                Some(errs) => {
+                    *errs += e;
+                }
+                None => errors = Some(e),
+            }
+        }
+
         if s.instance_type.is_none() {
             return match errors {
                 None => Ok(()),
@@ -159,7 +448,7 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
         }
 
         if let Err(e) = value.validate(
-            SchemaValidator::new(&self.defs, SchemaRef::from(*s))
+            SchemaValidator::new(self.ctx, SchemaRef::from(*s))
                 .with_spans(self.parent_span.clone(), value.span()),
         ) {
             match &mut errors {
@@ -176,6 +465,108 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
         }
     }
 
+    /// Validate the keywords that need the entire value,
+    /// these are enum, const and the custom keywords.
+    ///
+    /// The value is compared as JSON, so this works for
+    /// arrays and objects as well.
+    fn validate_json<V: ?Sized + Validate<Span = S>>(
+        &self,
+        schema: &SchemaObject,
+        value: &V,
+    ) -> Result<(), Errors<S>> {
+        let mut keywords = schema
+            .extensions
+            .iter()
+            .filter_map(|(k, v)| self.ctx.keywords.get(k).map(|kw| (k, v, kw)))
+            .peekable();
+
+        if schema.enum_values.is_none() && schema.const_value.is_none() && keywords.peek().is_none()
+        {
+            return Ok(());
+        }
+
+        let value_span = self.parent_span.combined(value.span());
+
+        // The whole value is only collected if it is needed,
+        // otherwise it would be rebuilt for every nested schema.
+        let composite = |v: &Value| matches!(v, Value::Array(_) | Value::Object(_));
+        let collect_all = keywords.peek().is_some()
+            || schema.const_value.iter().any(composite)
+            || schema.enum_values.iter().flatten().any(composite);
+
+        // Values that cannot be represented as JSON
+        // will not match anything, neither will arrays
+        // and objects if only scalars are expected.
+        let json_value = if collect_all {
+            to_json(value).ok()
+        } else {
+            to_json_scalar(value).ok().flatten()
+        };
+
+        let mut errors = Errors::new();
+
+        if let Some(enum_vals) = &schema.enum_values {
+            let enum_contains = match &json_value {
+                Some(v) => enum_vals.iter().any(|e| json_eq(e, v)),
+                None => false,
+            };
+
+            if !enum_contains {
+                errors.0.push(Error::schema(
+                    schema,
+                    value_span.clone(),
+                    ErrorValue::InvalidEnumValue {
+                        expected: enum_vals.clone(),
+                    },
+                ));
+            }
+        }
+
+        if let Some(const_val) = &schema.const_value {
+            let const_matches = match &json_value {
+                Some(v) => json_eq(const_val, v),
+                None => false,
+            };
+
+            if !const_matches {
+                errors.0.push(Error::schema(
+                    schema,
+                    value_span.clone(),
+                    ErrorValue::InvalidConstValue {
+                        expected: const_val.clone(),
+                    },
+                ));
+            }
+        }
+
+        if let Some(json_value) = &json_value {
+            for (name, keyword_value, keyword) in keywords {
+                if let Err(message) = keyword.validate(keyword_value, json_value) {
+                    let error = Error::schema(
+                        schema,
+                        value_span.clone(),
+                        ErrorValue::Keyword {
+                            keyword: name.clone(),
+                            message,
+                        },
+                    );
+
+                    match keyword.severity() {
+                        Severity::Error => errors.0.push(error),
+                        Severity::Warning => self.ctx.warn(error),
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Validate all the allOf anyOf, etc. schemas for a given value.
     fn validate_subschemas<V: ?Sized + Validate<Span = S>>(
         &self,
@@ -187,7 +578,7 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
 
             if let Some(all_of) = &sub.all_of {
                 for s in all_of {
-                    if let Err(e) = SchemaValidator::new(self.defs, s.into())
+                    if let Err(e) = SchemaValidator::new(self.ctx, s.into())
                         .with_spans(self.parent_span.clone(), self.span.clone())
                         .validate_inner(value)
                     {
@@ -200,7 +591,8 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                 let mut validated = Vec::with_capacity(any_of.len());
                 let mut inner_errors: Vec<Errors<_>> = Vec::with_capacity(any_of.len());
                 for s in any_of {
-                    match SchemaValidator::new(self.defs, s.into())
+                    let mark = self.ctx.mark();
+                    match SchemaValidator::new(self.ctx, s.into())
                         .with_spans(self.parent_span.clone(), self.span.clone())
                         .validate_inner(value)
                     {
@@ -213,13 +605,14 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                             }
                         },
                         Err(e) => {
+                            self.ctx.discard(mark);
                             inner_errors.push(e);
                         }
                     }
                 }
                 if validated.is_empty() {
-                    errors.push(Error::new(
-                        schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        schema,
                         value.span(),
                         ErrorValue::NoneValid {
                             exclusive: false,
@@ -236,11 +629,20 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                 }
             }
 
-            if let Some(one_of) = &sub.one_of {
+            if let Some(result) = sub
+                .one_of
+                .as_ref()
+                .and_then(|one_of| self.validate_discriminated(schema, one_of, value))
+            {
+                if let Err(e) = result {
+                    errors.extend(e.0);
+                }
+            } else if let Some(one_of) = &sub.one_of {
                 let mut validated = Vec::with_capacity(one_of.len());
                 let mut inner_errors: Vec<Errors<_>> = Vec::with_capacity(one_of.len());
                 for s in one_of {
-                    match SchemaValidator::new(self.defs, s.into())
+                    let mark = self.ctx.mark();
+                    match SchemaValidator::new(self.ctx, s.into())
                         .with_spans(self.parent_span.clone(), self.span.clone())
                         .validate_inner(value)
                     {
@@ -253,13 +655,14 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                             }
                         },
                         Err(e) => {
+                            self.ctx.discard(mark);
                             inner_errors.push(e);
                         }
                     }
                 }
                 if validated.is_empty() {
-                    errors.push(Error::new(
-                        schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        schema,
                         value.span(),
                         ErrorValue::NoneValid {
                             exclusive: true,
@@ -274,8 +677,8 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
                         },
                     ));
                 } else if validated.len() > 1 {
-                    errors.push(Error::new(
-                        schema.metadata.clone(),
+                    errors.push(Error::schema(
+                        schema,
                         value.span(),
                         ErrorValue::MoreThanOneValid {
                             schemas: one_of
@@ -292,19 +695,22 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
             }
 
             if let (Some(sub_if), Some(sub_then)) = (&sub.if_schema, &sub.then_schema) {
-                if SchemaValidator::new(self.defs, (&**sub_if).into())
+                let mark = self.ctx.mark();
+                let matched = SchemaValidator::new(self.ctx, (&**sub_if).into())
                     .with_spans(self.parent_span.clone(), self.span.clone())
                     .validate_inner(value)
-                    .is_ok()
-                {
-                    if let Err(e) = SchemaValidator::new(self.defs, (&**sub_then).into())
+                    .is_ok();
+                self.ctx.discard(mark);
+
+                if matched {
+                    if let Err(e) = SchemaValidator::new(self.ctx, (&**sub_then).into())
                         .with_spans(self.parent_span.clone(), self.span.clone())
                         .validate_inner(value)
                     {
                         errors.extend(e.0.into_iter());
                     }
                 } else if let Some(sub_else) = &sub.else_schema {
-                    if let Err(e) = SchemaValidator::new(self.defs, (&**sub_else).into())
+                    if let Err(e) = SchemaValidator::new(self.ctx, (&**sub_else).into())
                         .with_spans(self.parent_span.clone(), self.span.clone())
                         .validate_inner(value)
                     {
@@ -314,13 +720,16 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
             }
 
             if let Some(not) = &sub.not {
-                if SchemaValidator::new(self.defs, (&**not).into())
+                let mark = self.ctx.mark();
+                let matched = SchemaValidator::new(self.ctx, (&**not).into())
                     .with_spans(self.parent_span.clone(), self.span.clone())
                     .validate_inner(value)
-                    .is_ok()
-                {
-                    errors.push(Error::new(
-                        schema.metadata.clone(),
+                    .is_ok();
+                self.ctx.discard(mark);
+
+                if matched {
+                    errors.push(Error::schema(
+                        schema,
                         value.span(),
                         ErrorValue::ValidNot {
                             matched: match &**not {
@@ -342,6 +751,168 @@ impl<'a, S: Span> SchemaValidator<'a, S> {
         Ok(())
     }
 
+    /// Validate a value only against the `oneOf` subschema
+    /// that is selected by its discriminator property.
+    ///
+    /// Returns [None](Option::None) if the subschemas have no discriminator,
+    /// or the value does not have the property, in that case
+    /// every subschema must be tried.
+    fn validate_discriminated<V: ?Sized + Validate<Span = S>>(
+        &self,
+        schema: &SchemaObject,
+        one_of: &'a [Schema],
+        value: &V,
+    ) -> Option<Result<(), Errors<S>>> {
+        let discriminator = self
+            .ctx
+            .discriminators
+            .borrow_mut()
+            .entry(schema as *const SchemaObject)
+            .or_insert_with(|| self.discriminator(schema, one_of).map(Rc::new))
+            .clone()?;
+        let (property, variants) = &*discriminator;
+
+        let tag = to_json_property(value, property).ok()??;
+
+        match variants.iter().find(|(t, _)| json_eq(t, &tag)) {
+            Some((_, i)) => Some(
+                SchemaValidator::new(self.ctx, (&one_of[*i]).into())
+                    .with_spans(self.parent_span.clone(), self.span.clone())
+                    .validate_inner(value),
+            ),
+            None => Some(Err(Errors::one(Error::schema(
+                schema,
+                self.parent_span.combined(value.span()),
+                ErrorValue::UnknownVariant {
+                    property: property.clone(),
+                    tag,
+                    expected: variants.iter().map(|(t, _)| t.clone()).collect(),
+                },
+            )))),
+        }
+    }
+
+    /// Find the discriminator property of `oneOf` subschemas,
+    /// and the tag values with the index of the subschema they select.
+    ///
+    /// The property is either given with the OpenAPI `discriminator` extension,
+    /// or inferred from a required property that has a `const` or `enum` value
+    /// in every subschema, this is what Schemars generates for internally tagged enums.
+    fn discriminator(
+        &self,
+        schema: &SchemaObject,
+        one_of: &'a [Schema],
+    ) -> Option<Discriminator> {
+        if let Some(discriminator) = schema.extensions.get("discriminator") {
+            let property = discriminator.get("propertyName")?.as_str()?;
+            let mapping = discriminator.get("mapping").and_then(Value::as_object);
+
+            let mut variants = Vec::new();
+
+            for (i, s) in one_of.iter().enumerate() {
+                if let Schema::Object(SchemaObject {
+                    reference: Some(r), ..
+                }) = s
+                {
+                    let mapped = mapping
+                        .into_iter()
+                        .flatten()
+                        .filter(|(_, target)| target.as_str() == Some(r.as_str()))
+                        .map(|(tag, _)| (Value::String(tag.clone()), i))
+                        .collect::<Vec<_>>();
+
+                    // The name of the definition is the tag by default.
+                    if mapped.is_empty() {
+                        if let Some(name) = r.rsplit('/').next() {
+                            variants.push((Value::String(name.to_string()), i));
+                        }
+                    }
+
+                    variants.extend(mapped);
+                }
+
+                if let Some(tags) = self.variant_tags(s, property, false) {
+                    variants.extend(tags.into_iter().map(|t| (t, i)));
+                }
+            }
+
+            return Some((property.to_string(), variants));
+        }
+
+        let first = self.resolve(one_of.first()?)?;
+
+        first.object.as_ref()?.required.iter().find_map(|property| {
+            let mut variants = Vec::new();
+
+            for (i, s) in one_of.iter().enumerate() {
+                for tag in self.variant_tags(s, property, true)? {
+                    if variants.iter().any(|(t, _)| json_eq(t, &tag)) {
+                        return None;
+                    }
+                    variants.push((tag, i));
+                }
+            }
+
+            Some((property.clone(), variants))
+        })
+    }
+
+    /// The constant values of a property in a subschema.
+    fn variant_tags(
+        &self,
+        schema: &'a Schema,
+        property: &str,
+        required: bool,
+    ) -> Option<Vec<Value>> {
+        let obj = self.resolve(schema)?.object.as_ref()?;
+
+        if required && !obj.required.contains(property) {
+            return None;
+        }
+
+        let prop = self.resolve(obj.properties.get(property)?)?;
+
+        match (&prop.const_value, &prop.enum_values) {
+            (Some(c), _) => Some(vec![c.clone()]),
+            (None, Some(e)) if !e.is_empty() => Some(e.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether a required property is required in the direction of the data.
+    fn required_in_direction(&self, property: &'a Schema) -> bool {
+        let direction = match self.ctx.direction {
+            Some(d) => d,
+            None => return true,
+        };
+
+        // The metadata can be next to the reference, or in the referenced schema.
+        let own = match property {
+            Schema::Object(o) => o.metadata.as_deref(),
+            Schema::Bool(_) => None,
+        };
+        let resolved = self.resolve(property).and_then(|o| o.metadata.as_deref());
+
+        !own.into_iter().chain(resolved).any(|m| match direction {
+            Direction::Request => m.read_only,
+            Direction::Response => m.write_only,
+        })
+    }
+
+    /// Resolve a local reference if there is any.
+    fn resolve(&self, schema: &'a Schema) -> Option<&'a SchemaObject> {
+        match schema {
+            Schema::Bool(_) => None,
+            Schema::Object(SchemaObject {
+                reference: Some(r), ..
+            }) => match self.ctx.defs.get(local_definition(r)?)? {
+                Schema::Object(o) => Some(o),
+                Schema::Bool(_) => None,
+            },
+            Schema::Object(o) => Some(o),
+        }
+    }
+
     fn with_spans(mut self, parent: Option<S>, span: Option<S>) -> Self {
         self.parent_span = parent;
         self.span = span;
@@ -387,11 +958,10 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         self
     }
 
-    fn validate_bool(self, v: bool) -> Result<(), Self::Error> {
+    fn validate_bool(self, _v: bool) -> Result<(), Self::Error> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Boolean, s, &self.combined_span)?;
-        check_enum!(bool, v, s, &self.combined_span)?;
 
         Ok(())
     }
@@ -400,7 +970,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -410,7 +979,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -420,7 +988,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -430,7 +997,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -440,7 +1006,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -450,7 +1015,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -460,7 +1024,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -470,7 +1033,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -480,7 +1042,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -490,7 +1051,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Integer, s, &self.combined_span)?;
-        check_enum!(int, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -500,7 +1060,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Number, s, &self.combined_span)?;
-        check_enum!(float, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -510,7 +1069,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(Number, s, &self.combined_span)?;
-        check_enum!(float, v, s, &self.combined_span)?;
         check_number!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -524,7 +1082,6 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         let s = not_bool_schema!(&self.schema, &self.combined_span);
 
         check_type!(String, s, &self.combined_span)?;
-        check_enum!(str, v, s, &self.combined_span)?;
         check_string!(v, s, &self.combined_span)?;
 
         Ok(())
@@ -589,7 +1146,7 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
             }
         }
         if let Some(l) = len {
-            self.arr_hashes.reserve(l);
+            self.arr_items.reserve(l);
         }
 
         self.parent_span = self.combined_span.clone();
@@ -603,7 +1160,17 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
         check_type!(s, &self.combined_span)?;
 
         if let Some(obj) = &s.object {
-            self.obj_required = obj.required.clone();
+            if !self.ctx.partial {
+                self.obj_required = obj
+                    .required
+                    .iter()
+                    .filter(|p| match obj.properties.get(*p) {
+                        Some(prop) => self.required_in_direction(prop),
+                        None => true,
+                    })
+                    .cloned()
+                    .collect();
+            }
         }
 
         self.parent_span = self.combined_span.clone();
@@ -632,9 +1199,9 @@ impl<'a, S: Span> Validator<S> for SchemaValidator<'a, S> {
             }
 
             for (k, v) in obj.pattern_properties.iter() {
-                let key_re = regex::Regex::new(k).map_err(|error| {
-                    Errors::one(Error::new(
-                        s.metadata.clone(),
+                let key_re = cached_regex(k).map_err(|error| {
+                    Errors::one(Error::schema(
+                        s,
                         tag_span.clone(),
                         ErrorValue::InvalidSchema(InvalidSchema::InvalidPattern {
                             pattern: k.clone(),
@@ -696,11 +1263,14 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
 
         if let Some(arr) = &s.array {
             if let Some(c) = self.arr_contains {
-                if SchemaValidator::new(&self.defs, c.into())
+                let mark = self.ctx.mark();
+                let matched = SchemaValidator::new(self.ctx, c.into())
                     .with_parent_span(self.parent_span.clone())
                     .validate_inner(value)
-                    .is_ok()
-                {
+                    .is_ok();
+                self.ctx.discard(mark);
+
+                if matched {
                     self.arr_contains = None;
                 }
             }
@@ -708,7 +1278,7 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
             if let Some(items) = &arr.items {
                 match items {
                     SingleOrVec::Single(single_schema) => {
-                        if let Err(e) = SchemaValidator::new(&self.defs, (&**single_schema).into())
+                        if let Err(e) = SchemaValidator::new(self.ctx, (&**single_schema).into())
                             .with_parent_span(self.parent_span.clone())
                             .validate_inner(value)
                         {
@@ -717,14 +1287,14 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
                     }
                     SingleOrVec::Vec(schemas) => {
                         if let Some(s) = schemas.get(self.arr_item_count - 1) {
-                            if let Err(e) = SchemaValidator::new(&self.defs, s.into())
+                            if let Err(e) = SchemaValidator::new(self.ctx, s.into())
                                 .with_parent_span(self.parent_span.clone())
                                 .validate_inner(value)
                             {
                                 errors.0.extend(e.0.into_iter());
                             }
                         } else if let Some(s) = &arr.additional_items {
-                            if let Err(e) = SchemaValidator::new(&self.defs, (&**s).into())
+                            if let Err(e) = SchemaValidator::new(self.ctx, (&**s).into())
                                 .with_parent_span(self.parent_span.clone())
                                 .validate_inner(value)
                             {
@@ -736,21 +1306,28 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
             }
 
             if let Some(true) = arr.unique_items {
-                let mut hasher = DefaultHasher::new();
-                value.hash(&mut hasher);
-                let h = hasher.finish();
-
-                let existing = self.arr_hashes.insert(h, value_span.clone());
-
-                if let Some(existing_val) = existing {
-                    errors.0.push(Error::new(
-                        s.metadata.clone(),
-                        value_span.clone(),
-                        ErrorValue::NotUnique {
-                            first: existing_val,
-                            duplicate: value_span.clone(),
-                        },
-                    ));
+                // Values that cannot be represented as JSON
+                // are not checked for uniqueness.
+                if let Ok(json_value) = to_json(value) {
+                    let mut hasher = DefaultHasher::new();
+                    json_hash(&json_value, &mut hasher);
+                    let h = hasher.finish();
+
+                    let same_hash = self.arr_items.entry(h).or_default();
+
+                    match same_hash.iter().find(|(v, _)| json_eq(v, &json_value)) {
+                        Some((_, existing_span)) => {
+                            errors.0.push(Error::schema(
+                                s,
+                                value_span.clone(),
+                                ErrorValue::NotUnique {
+                                    first: existing_span.clone(),
+                                    duplicate: value_span.clone(),
+                                },
+                            ));
+                        }
+                        None => same_hash.push((json_value, value_span.clone())),
+                    }
                 }
             }
         }
@@ -771,8 +1348,8 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
         let mut errors = Errors::new();
 
         if let Some(c) = self.arr_contains {
-            errors.0.push(Error::new(
-                s.metadata.clone(),
+            errors.0.push(Error::schema(
+                s,
                 self.combined_span.clone(),
                 ErrorValue::MustContain {
                     schema: match c {
@@ -786,8 +1363,8 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
         if let Some(arr) = &s.array {
             if let Some(min) = arr.min_items {
                 if self.arr_item_count < min as usize {
-                    errors.0.push(Error::new(
-                        s.metadata.clone(),
+                    errors.0.push(Error::schema(
+                        s,
                         self.combined_span.clone(),
                         ErrorValue::NotEnoughItems { min: min as usize },
                     ));
@@ -796,8 +1373,8 @@ impl<'a, S: Span> ValidateSeq<S> for SchemaValidator<'a, S> {
 
             if let Some(max) = arr.max_items {
                 if self.arr_item_count > max as usize {
-                    errors.0.push(Error::new(
-                        s.metadata.clone(),
+                    errors.0.push(Error::schema(
+                        s,
                         self.combined_span.clone(),
                         ErrorValue::TooManyItems { max: max as usize },
                     ));
@@ -873,7 +1450,7 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
 
         if let Some(obj) = &s.object {
             if let Some(name_schema) = &obj.property_names {
-                if let Err(e) = SchemaValidator::new(&self.defs, (&**name_schema).into())
+                if let Err(e) = SchemaValidator::new(self.ctx, (&**name_schema).into())
                     .with_spans(self.parent_span.clone(), key_span)
                     .validate_inner(key)
                 {
@@ -898,7 +1475,7 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
 
         if let Some(obj) = &s.object {
             if let Some(prop_schema) = obj.properties.get(&key) {
-                match SchemaValidator::new(&self.defs, prop_schema.into())
+                match SchemaValidator::new(self.ctx, prop_schema.into())
                     .with_parent_span(self.parent_span.clone())
                     .validate_inner(value)
                 {
@@ -912,9 +1489,9 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
             }
 
             for (k, v) in obj.pattern_properties.iter() {
-                let key_re = regex::Regex::new(k).map_err(|error| {
-                    Errors::one(Error::new(
-                        s.metadata.clone(),
+                let key_re = cached_regex(k).map_err(|error| {
+                    Errors::one(Error::schema(
+                        s,
                         value.span(),
                         ErrorValue::InvalidSchema(InvalidSchema::InvalidPattern {
                             pattern: k.clone(),
@@ -924,7 +1501,7 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
                 })?;
 
                 if key_re.is_match(&key) {
-                    match SchemaValidator::new(&self.defs, v.into())
+                    match SchemaValidator::new(self.ctx, v.into())
                         .with_parent_span(self.parent_span.clone())
                         .validate_inner(value)
                     {
@@ -939,13 +1516,13 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
             }
 
             if let Some(add_prop_schema) = &obj.additional_properties {
-                if let Err(e) = SchemaValidator::new(&self.defs, (&**add_prop_schema).into())
+                if let Err(e) = SchemaValidator::new(self.ctx, (&**add_prop_schema).into())
                     .with_parent_span(self.parent_span.clone())
                     .validate_inner(value)
                 {
                     if let ErrorValue::Never = &e.0.get(0).unwrap().value {
-                        return Err(Errors::one(Error::new(
-                            s.metadata.clone(),
+                        return Err(Errors::one(Error::schema(
+                            s,
                             self.obj_last_key_span.take(),
                             ErrorValue::UnknownProperty,
                         )));
@@ -970,8 +1547,8 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
         if let Some(obj) = &s.object {
             if let Some(max) = obj.max_properties {
                 if self.obj_prop_count > max as usize {
-                    errors.0.push(Error::new(
-                        s.metadata.clone(),
+                    errors.0.push(Error::schema(
+                        s,
                         self.combined_span.clone(),
                         ErrorValue::TooManyProperties { max: max as usize },
                     ))
@@ -980,8 +1557,8 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
 
             if let Some(min) = obj.min_properties {
                 if self.obj_prop_count < min as usize {
-                    errors.0.push(Error::new(
-                        s.metadata.clone(),
+                    errors.0.push(Error::schema(
+                        s,
                         self.combined_span.clone(),
                         ErrorValue::NotEnoughProperties { min: min as usize },
                     ))
@@ -990,8 +1567,8 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
         }
 
         for p in self.obj_required {
-            errors.0.push(Error::new(
-                s.metadata.clone(),
+            errors.0.push(Error::schema(
+                s,
                 self.combined_span.clone(),
                 ErrorValue::RequiredProperty { name: p },
             ))
@@ -1014,10 +1591,10 @@ impl<'a, S: Span> ValidateMap<S> for SchemaValidator<'a, S> {
     }
 }
 
-fn local_definition(path: &str) -> Option<&str> {
-    if !path.starts_with("#/definitions/") {
-        return None;
-    }
-
-    Some(path.trim_start_matches("#/definitions/"))
+/// The name of the definition if the reference is local,
+/// OpenAPI component references are also accepted.
+pub(super) fn local_definition(path: &str) -> Option<&str> {
+    ["#/definitions/", "#/components/schemas/"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
 }
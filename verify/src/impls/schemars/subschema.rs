@@ -0,0 +1,209 @@
+//! Validation against a part of a schema.
+
+use super::{
+    keywords::{Keyword, Keywords},
+    schema::{verify_schema, SchemaRef},
+    Errors,
+};
+use crate::{span::Span, Validate, Verifier};
+use schemars_crate::{
+    schema::{RootSchema, Schema, SchemaObject, SingleOrVec},
+    Map,
+};
+use std::sync::Arc;
+
+/// A schema inside another schema, returned by [subschema](SubschemaExt::subschema).
+///
+/// References are resolved against the definitions of the [RootSchema](RootSchema)
+/// it was found in.
+///
+/// Custom keywords are only validated if they are registered with [keyword](Subschema::keyword),
+/// or the subschema is returned by [SchemaVerifier::subschema](super::SchemaVerifier::subschema).
+#[derive(Clone)]
+pub struct Subschema<'s> {
+    schema: SchemaRef<'s>,
+    definitions: Option<&'s Map<String, Schema>>,
+    keywords: Keywords,
+}
+
+impl<'s> Subschema<'s> {
+    fn new(schema: SchemaRef<'s>, definitions: Option<&'s Map<String, Schema>>) -> Self {
+        Self {
+            schema,
+            definitions,
+            keywords: Keywords::new(),
+        }
+    }
+
+    /// Register a handler for a custom keyword.
+    ///
+    /// The handler is called for every schema that contains the keyword,
+    /// read more [here](super::keywords).
+    pub fn keyword<K: Keyword + 'static>(mut self, name: impl Into<String>, keyword: K) -> Self {
+        self.keywords.insert(name.into(), Arc::new(keyword));
+        self
+    }
+
+    pub(super) fn with_keywords(mut self, keywords: Keywords) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// The schema object, it is [None](Option::None) for boolean schemas.
+    pub fn schema_object(&self) -> Option<&'s SchemaObject> {
+        match self.schema {
+            SchemaRef::Bool(_) => None,
+            SchemaRef::Object(o) => Some(o),
+        }
+    }
+}
+
+impl<'s> core::fmt::Debug for Subschema<'s> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.schema {
+            SchemaRef::Bool(b) => f.debug_tuple("Subschema").field(&b).finish(),
+            SchemaRef::Object(o) => f.debug_tuple("Subschema").field(o).finish(),
+        }
+    }
+}
+
+impl<'s, S: Span> Verifier<S> for Subschema<'s> {
+    type Error = Errors<S>;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        self.verify_value_with_span(value, None)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        match self.definitions {
+            Some(defs) => verify_schema(self.schema, defs, &self.keywords, None, value, span),
+            None => verify_schema(
+                self.schema,
+                &Default::default(),
+                &self.keywords,
+                None,
+                value,
+                span,
+            ),
+        }
+    }
+}
+
+/// Look up schemas by JSON Pointers such as `#/definitions/Address`
+/// or `#/properties/items/items`.
+///
+/// ```ignore
+/// let address = root.subschema("#/definitions/Address").unwrap();
+/// address.verify_value(&Spanned::new(&value, KeySpans::default()))?;
+/// ```
+///
+/// Definitions are only available in a [RootSchema](RootSchema),
+/// OpenAPI style `#/components/schemas/` pointers are also accepted for them.
+pub trait SubschemaExt {
+    /// Return the schema at the pointer, if there is any.
+    ///
+    /// The pointer is a URI fragment, it must start with `#`.
+    fn subschema(&self, pointer: &str) -> Option<Subschema<'_>>;
+}
+
+impl SubschemaExt for RootSchema {
+    fn subschema(&self, pointer: &str) -> Option<Subschema<'_>> {
+        let mut tokens = tokens(pointer)?.peekable();
+
+        let schema = match tokens.peek().map(String::as_str) {
+            Some("definitions") | Some("$defs") => {
+                tokens.next();
+                self.definitions.get(&tokens.next()?)?.into()
+            }
+            Some("components") => {
+                tokens.next();
+                if tokens.next()? != "schemas" {
+                    return None;
+                }
+                self.definitions.get(&tokens.next()?)?.into()
+            }
+            _ => SchemaRef::Object(&self.schema),
+        };
+
+        Some(Subschema::new(
+            walk(schema, tokens)?,
+            Some(&self.definitions),
+        ))
+    }
+}
+
+impl SubschemaExt for SchemaObject {
+    fn subschema(&self, pointer: &str) -> Option<Subschema<'_>> {
+        Some(Subschema::new(walk(self.into(), tokens(pointer)?)?, None))
+    }
+}
+
+impl SubschemaExt for Schema {
+    fn subschema(&self, pointer: &str) -> Option<Subschema<'_>> {
+        Some(Subschema::new(walk(self.into(), tokens(pointer)?)?, None))
+    }
+}
+
+/// The unescaped tokens of the pointer.
+fn tokens(pointer: &str) -> Option<impl Iterator<Item = String> + '_> {
+    let pointer = pointer.strip_prefix('#')?;
+
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+
+    Some(
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|t| t.replace("~1", "/").replace("~0", "~")),
+    )
+}
+
+/// Follow the tokens through the keywords that contain schemas.
+fn walk<'s>(
+    mut schema: SchemaRef<'s>,
+    mut tokens: impl Iterator<Item = String>,
+) -> Option<SchemaRef<'s>> {
+    while let Some(token) = tokens.next() {
+        let s = match schema {
+            SchemaRef::Bool(_) => return None,
+            SchemaRef::Object(s) => s,
+        };
+
+        let next: &Schema = match token.as_str() {
+            "properties" => s.object.as_ref()?.properties.get(&tokens.next()?)?,
+            "patternProperties" => s.object.as_ref()?.pattern_properties.get(&tokens.next()?)?,
+            "additionalProperties" => s.object.as_ref()?.additional_properties.as_ref()?,
+            "propertyNames" => s.object.as_ref()?.property_names.as_ref()?,
+            "items" => match s.array.as_ref()?.items.as_ref()? {
+                SingleOrVec::Single(item) => item,
+                SingleOrVec::Vec(items) => items.get(tokens.next()?.parse::<usize>().ok()?)?,
+            },
+            "additionalItems" => s.array.as_ref()?.additional_items.as_ref()?,
+            "contains" => s.array.as_ref()?.contains.as_ref()?,
+            "allOf" | "anyOf" | "oneOf" => {
+                let sub = s.subschemas.as_ref()?;
+                let schemas = match token.as_str() {
+                    "allOf" => sub.all_of.as_ref()?,
+                    "anyOf" => sub.any_of.as_ref()?,
+                    _ => sub.one_of.as_ref()?,
+                };
+                schemas.get(tokens.next()?.parse::<usize>().ok()?)?
+            }
+            "not" => s.subschemas.as_ref()?.not.as_ref()?,
+            "if" => s.subschemas.as_ref()?.if_schema.as_ref()?,
+            "then" => s.subschemas.as_ref()?.then_schema.as_ref()?,
+            "else" => s.subschemas.as_ref()?.else_schema.as_ref()?,
+            _ => return None,
+        };
+
+        schema = next.into();
+    }
+
+    Some(schema)
+}
@@ -0,0 +1,437 @@
+//! Helpers for working with values as JSON.
+//!
+//! Some keywords (`enum`, `const`, `uniqueItems`) need to compare
+//! whole values instead of validating them piece by piece,
+//! so the values are collected into [serde_json::Value](serde_json::Value)s first.
+
+use crate::{span::Span, Validate, ValidateMap, ValidateSeq, Validator};
+use serde_json::{Map, Number, Value};
+use std::{
+    collections::hash_map::DefaultHasher,
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use super::errors::{Error, ErrorValue, Errors, UnsupportedValue};
+
+/// Collect any validated value into a JSON value.
+///
+/// It returns an error if the value cannot be represented as JSON,
+/// for example if it is a map with non-string keys.
+#[allow(clippy::result_large_err)]
+pub(super) fn to_json<V: ?Sized + Validate>(value: &V) -> Result<Value, Errors<V::Span>> {
+    let mut out = Value::Null;
+    value.validate(ValueCollector::new(&mut out, Collect::All))?;
+    Ok(out)
+}
+
+/// Collect a value into a JSON value only if it is not an array or an object.
+///
+/// The items of arrays and objects are not visited,
+/// [None](Option::None) is returned for them instead.
+#[allow(clippy::result_large_err)]
+pub(super) fn to_json_scalar<V: ?Sized + Validate>(
+    value: &V,
+) -> Result<Option<Value>, Errors<V::Span>> {
+    let mut out = Value::Null;
+    value.validate(ValueCollector::new(&mut out, Collect::Shallow))?;
+
+    match out {
+        Value::Array(_) | Value::Object(_) => Ok(None),
+        v => Ok(Some(v)),
+    }
+}
+
+/// Collect only a property of an object into a JSON value.
+///
+/// The other properties are not visited, [None](Option::None)
+/// is returned if the value is not an object or the property is missing.
+#[allow(clippy::result_large_err)]
+pub(super) fn to_json_property<V: ?Sized + Validate>(
+    value: &V,
+    property: &str,
+) -> Result<Option<Value>, Errors<V::Span>> {
+    let mut out = Value::Null;
+    value.validate(ValueCollector::new(&mut out, Collect::Property(property)))?;
+
+    match out {
+        Value::Object(mut obj) => Ok(obj.remove(property)),
+        _ => Ok(None),
+    }
+}
+
+/// Compare two values with JSON semantics.
+///
+/// Numbers are compared by their mathematical value, so `1` equals `1.0`,
+/// and the order of object properties does not matter.
+pub(super) fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => number_eq(a, b),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a
+                    .iter()
+                    .all(|(k, v)| b.get(k).map(|b_v| json_eq(v, b_v)).unwrap_or(false))
+        }
+        _ => a == b,
+    }
+}
+
+/// Hash a value in a way that is consistent with [json_eq](json_eq).
+///
+/// Equal hashes do not guarantee equal values,
+/// the values must still be compared afterwards.
+pub(super) fn json_hash<H: Hasher>(value: &Value, state: &mut H) {
+    match value {
+        Value::Null => state.write_u8(0),
+        Value::Bool(b) => {
+            state.write_u8(1);
+            b.hash(state);
+        }
+        Value::Number(n) => {
+            state.write_u8(2);
+            // Every number is hashed as a float, so that
+            // 1 and 1.0 end up with the same hash.
+            let f = n.as_f64().unwrap_or_default();
+            if f == 0f64 {
+                0f64.to_bits().hash(state);
+            } else {
+                f.to_bits().hash(state);
+            }
+        }
+        Value::String(s) => {
+            state.write_u8(3);
+            s.hash(state);
+        }
+        Value::Array(a) => {
+            state.write_u8(4);
+            a.len().hash(state);
+            for v in a {
+                json_hash(v, state);
+            }
+        }
+        Value::Object(o) => {
+            state.write_u8(5);
+            o.len().hash(state);
+
+            // The property order must not matter.
+            let mut props = 0u64;
+            for (k, v) in o {
+                let mut prop_state = DefaultHasher::new();
+                k.hash(&mut prop_state);
+                json_hash(v, &mut prop_state);
+                props = props.wrapping_add(prop_state.finish());
+            }
+            props.hash(state);
+        }
+    }
+}
+
+fn number_eq(a: &Number, b: &Number) -> bool {
+    if a.is_f64() || b.is_f64() {
+        return a.as_f64() == b.as_f64();
+    }
+
+    match (a.as_i64(), b.as_i64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.as_u64().is_some() && a.as_u64() == b.as_u64(),
+    }
+}
+
+fn float_value(v: f64) -> Value {
+    Number::from_f64(v).map(Value::Number).unwrap_or(Value::Null)
+}
+
+/// What a [ValueCollector](ValueCollector) collects.
+#[derive(Clone, Copy)]
+enum Collect<'v> {
+    /// The entire value.
+    All,
+    /// Only the value itself, the items of sequences and maps are skipped.
+    Shallow,
+    /// Only the given property of a map.
+    Property(&'v str),
+}
+
+/// A validator that does not validate anything,
+/// instead it rebuilds the value as JSON.
+struct ValueCollector<'v, S: Span> {
+    out: &'v mut Value,
+
+    // Serde's externally tagged variants
+    // are wrapped in an object.
+    tag: Option<String>,
+
+    collect: Collect<'v>,
+
+    _span: PhantomData<S>,
+}
+
+impl<'v, S: Span> ValueCollector<'v, S> {
+    fn new(out: &'v mut Value, collect: Collect<'v>) -> Self {
+        Self {
+            out,
+            tag: None,
+            collect,
+            _span: PhantomData,
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn set(self, value: Value) -> Result<(), Errors<S>> {
+        *self.out = match self.tag {
+            Some(tag) => {
+                let mut m = Map::new();
+                m.insert(tag, value);
+                Value::Object(m)
+            }
+            None => value,
+        };
+        Ok(())
+    }
+}
+
+impl<'v, S: Span> Validator<S> for ValueCollector<'v, S> {
+    type Error = Errors<S>;
+
+    type ValidateSeq = SeqCollector<'v, S>;
+    type ValidateMap = MapCollector<'v, S>;
+
+    fn with_span(self, _span: Option<S>) -> Self {
+        self
+    }
+
+    fn validate_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.set(Value::Bool(v))
+    }
+
+    fn validate_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_i128(self, v: i128) -> Result<(), Self::Error> {
+        if let Ok(v) = i64::try_from(v) {
+            self.set(v.into())
+        } else if let Ok(v) = u64::try_from(v) {
+            self.set(v.into())
+        } else {
+            self.set(float_value(v as f64))
+        }
+    }
+
+    fn validate_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.set(v.into())
+    }
+
+    fn validate_u128(self, v: u128) -> Result<(), Self::Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.set(v.into()),
+            Err(_) => self.set(float_value(v as f64)),
+        }
+    }
+
+    fn validate_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.set(float_value(v as f64))
+    }
+
+    fn validate_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.set(float_value(v))
+    }
+
+    fn validate_char(self, v: char) -> Result<(), Self::Error> {
+        self.set(Value::String(v.to_string()))
+    }
+
+    fn validate_str(self, v: &str) -> Result<(), Self::Error> {
+        self.set(Value::String(v.to_string()))
+    }
+
+    fn validate_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.set(Value::Array(v.iter().map(|b| (*b).into()).collect()))
+    }
+
+    fn validate_none(self) -> Result<(), Self::Error> {
+        self.set(Value::Null)
+    }
+
+    fn validate_some<V>(self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        value.validate(self)
+    }
+
+    fn validate_unit(self) -> Result<(), Self::Error> {
+        self.set(Value::Null)
+    }
+
+    fn validate_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        self.set(Value::Null)
+    }
+
+    fn validate_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.set(Value::String(variant.to_string()))
+    }
+
+    fn validate_seq(self, len: Option<usize>) -> Result<Self::ValidateSeq, Self::Error> {
+        Ok(SeqCollector {
+            parent: self,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn validate_map(self, _len: Option<usize>) -> Result<Self::ValidateMap, Self::Error> {
+        Ok(MapCollector {
+            parent: self,
+            entries: Map::new(),
+            last_key: None,
+        })
+    }
+
+    fn validate_tag<V>(&mut self, tag: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        let tag = tag.to_string();
+
+        // The tag is the only property of the wrapping object.
+        if let Collect::Property(property) = self.collect {
+            self.collect = if property == tag {
+                Collect::All
+            } else {
+                Collect::Shallow
+            };
+        }
+
+        self.tag = Some(tag);
+        Ok(())
+    }
+}
+
+struct SeqCollector<'v, S: Span> {
+    parent: ValueCollector<'v, S>,
+    items: Vec<Value>,
+}
+
+impl<'v, S: Span> ValidateSeq<S> for SeqCollector<'v, S> {
+    type Error = Errors<S>;
+
+    fn with_span(&mut self, _span: Option<S>) -> &mut Self {
+        self
+    }
+
+    fn validate_element<V>(&mut self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + Hash,
+    {
+        if let Collect::All = self.parent.collect {
+            self.items.push(to_json(value)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.parent.set(Value::Array(self.items))
+    }
+}
+
+struct MapCollector<'v, S: Span> {
+    parent: ValueCollector<'v, S>,
+    entries: Map<String, Value>,
+    last_key: Option<String>,
+}
+
+impl<'v, S: Span> ValidateMap<S> for MapCollector<'v, S> {
+    type Error = Errors<S>;
+
+    fn with_span(&mut self, _span: Option<S>) -> &mut Self {
+        self
+    }
+
+    fn validate_key<V>(&mut self, key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        Err(Errors::one(Error::new(
+            None,
+            key.span(),
+            ErrorValue::UnsupportedValue(UnsupportedValue::KeyNotString),
+        )))
+    }
+
+    fn validate_string_key<V>(&mut self, key: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S> + ToString,
+    {
+        self.last_key = Some(key.to_string());
+        Ok(())
+    }
+
+    fn validate_value<V>(&mut self, value: &V) -> Result<(), Self::Error>
+    where
+        V: ?Sized + Validate<Span = S>,
+    {
+        let key = match self.last_key.take() {
+            Some(key) => key,
+            None => {
+                return Err(Errors::one(Error::new(
+                    None,
+                    value.span(),
+                    ErrorValue::UnsupportedValue(UnsupportedValue::ValueWithoutKey),
+                )))
+            }
+        };
+
+        let collect = match self.parent.collect {
+            Collect::All => true,
+            Collect::Shallow => false,
+            Collect::Property(property) => property == key,
+        };
+
+        if collect {
+            self.entries.insert(key, to_json(value)?);
+        }
+        Ok(())
+    }
+
+    fn string_key_required(&self) -> bool {
+        true
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.parent.set(Value::Object(self.entries))
+    }
+}
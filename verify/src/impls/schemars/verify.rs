@@ -1,26 +1,36 @@
 use crate::{span::Keys, Verify};
 use schemars_crate::{
-    schema::{RootSchema, Schema, SchemaObject},
+    schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec},
     Map,
 };
+use serde_json::Value;
 
-use super::errors::{Error, ErrorValue, Errors, InvalidSchema};
+use super::{
+    errors::{Error, ErrorValue, Errors, InvalidSchema},
+    schema::local_definition,
+};
 
+/// The schema is invalid if it has invalid references or patterns,
+/// use [lint](lint) to find other mistakes as well.
 impl Verify for RootSchema {
     type Error = Errors<Keys>;
 
     fn verify(&self) -> Result<(), Self::Error> {
-        let mut errors = Errors::new();
-
-        for (k, s) in &self.definitions {
-            if let Err(err) = verify_schema(s, &self.definitions, Keys::new() + "definitions" + k) {
-                errors += err;
-            }
-        }
-
-        if let Err(err) = verify_schema_object(&self.schema, &self.definitions, Keys::new()) {
-            errors += err;
-        }
+        let errors: Errors<Keys> = Errors(
+            lint(self)
+                .into_iter()
+                .filter(|e| {
+                    matches!(
+                        &e.value,
+                        ErrorValue::InvalidSchema(
+                            InvalidSchema::MissingDefinition(_)
+                                | InvalidSchema::ExternalReference(_)
+                                | InvalidSchema::InvalidPattern { .. }
+                        )
+                    )
+                })
+                .collect(),
+        );
 
         if errors.is_empty() {
             Ok(())
@@ -30,6 +40,44 @@ impl Verify for RootSchema {
     }
 }
 
+/// Check the schema for mistakes, the errors and warnings
+/// are spanned by the path of the keyword in the schema.
+///
+/// Besides invalid references and patterns, the errors are:
+///
+/// - limits that no value can satisfy, e.g. `minimum` greater than `maximum`,
+/// - required properties that are denied by `additionalProperties`,
+/// - definitions that always contain themselves through `$ref`, e.g. in a required property.
+///
+/// And the warnings are:
+///
+/// - `enum` and `const` values that do not match the `type`,
+/// - `oneOf` subschemas that can never be the only match, including duplicates,
+/// - string keywords such as `pattern` for types other than strings.
+pub fn lint(root: &RootSchema) -> Errors<Keys> {
+    let mut errors = Errors::new();
+
+    for (k, s) in &root.definitions {
+        if let Err(err) = verify_schema(s, &root.definitions, Keys::new() + "definitions" + k) {
+            errors += err;
+        }
+    }
+
+    if let Err(err) = verify_schema_object(&root.schema, &root.definitions, Keys::new()) {
+        errors += err;
+    }
+
+    for name in ref_cycles(&root.definitions) {
+        errors.0.push(Error::new(
+            None,
+            Some(Keys::new() + "definitions" + name),
+            ErrorValue::InvalidSchema(InvalidSchema::RefCycle(name.to_string())),
+        ));
+    }
+
+    errors
+}
+
 fn verify_schema(
     schema: &Schema,
     definitions: &Map<String, Schema>,
@@ -85,6 +133,34 @@ fn verify_schema_object(
                 if let Err(err) = verify_schema(s, definitions, span.clone() + "oneOf" + i) {
                     errors += err;
                 }
+
+                // Both copies are reported, as either of them can be removed.
+                if let Some(other) = one_ofs
+                    .iter()
+                    .enumerate()
+                    .position(|(j, o)| j != i && o == s)
+                {
+                    errors.0.push(
+                        Error::new(
+                            None,
+                            Some(span.clone() + "oneOf" + i),
+                            ErrorValue::InvalidSchema(InvalidSchema::DuplicateOneOf {
+                                index: i,
+                                other,
+                            }),
+                        )
+                        .warning(),
+                    );
+                } else if unreachable_one_of(schema, s) {
+                    errors.0.push(
+                        Error::new(
+                            None,
+                            Some(span.clone() + "oneOf" + i),
+                            ErrorValue::InvalidSchema(InvalidSchema::UnreachableOneOf { index: i }),
+                        )
+                        .warning(),
+                    );
+                }
             }
         }
 
@@ -141,14 +217,188 @@ fn verify_schema_object(
             }
         }
 
+        for (k, s) in &o.pattern_properties {
+            if let Err(err) = verify_schema(s, definitions, span.clone() + "patternProperties" + k)
+            {
+                errors += err;
+            }
+        }
+
         if let Some(s) = &o.additional_properties {
             if let Err(err) = verify_schema(s, definitions, span.clone() + "additionalProperties") {
                 errors += err;
             }
         }
+
+        if let Some(s) = &o.property_names {
+            if let Err(err) = verify_schema(s, definitions, span.clone() + "propertyNames") {
+                errors += err;
+            }
+        }
+
+        if o.additional_properties.as_deref() == Some(&Schema::Bool(false)) {
+            for name in &o.required {
+                let allowed = o.properties.contains_key(name)
+                    || o.pattern_properties.keys().any(|p| {
+                        regex::Regex::new(p)
+                            .map(|r| r.is_match(name))
+                            .unwrap_or(false)
+                    });
+
+                if !allowed {
+                    errors.0.push(Error::new(
+                        None,
+                        Some(span.clone() + "required"),
+                        ErrorValue::InvalidSchema(InvalidSchema::RequiredNotAllowed(name.clone())),
+                    ));
+                }
+            }
+        }
+
+        check_range(
+            o.min_properties.map(f64::from),
+            o.max_properties.map(f64::from),
+            false,
+            ("minProperties", "maxProperties"),
+            &span,
+            &mut errors,
+        );
+    }
+
+    if let Some(a) = &schema.array {
+        match &a.items {
+            Some(SingleOrVec::Single(s)) => {
+                if let Err(err) = verify_schema(s, definitions, span.clone() + "items") {
+                    errors += err;
+                }
+            }
+            Some(SingleOrVec::Vec(items)) => {
+                for (i, s) in items.iter().enumerate() {
+                    if let Err(err) = verify_schema(s, definitions, span.clone() + "items" + i) {
+                        errors += err;
+                    }
+                }
+            }
+            None => {}
+        }
+
+        if let Some(s) = &a.additional_items {
+            if let Err(err) = verify_schema(s, definitions, span.clone() + "additionalItems") {
+                errors += err;
+            }
+        }
+
+        if let Some(s) = &a.contains {
+            if let Err(err) = verify_schema(s, definitions, span.clone() + "contains") {
+                errors += err;
+            }
+        }
+
+        check_range(
+            a.min_items.map(f64::from),
+            a.max_items.map(f64::from),
+            false,
+            ("minItems", "maxItems"),
+            &span,
+            &mut errors,
+        );
+    }
+
+    if let Some(n) = &schema.number {
+        for (min, max, keywords) in &[
+            (n.minimum, n.maximum, ("minimum", "maximum")),
+            (
+                n.exclusive_minimum,
+                n.maximum,
+                ("exclusiveMinimum", "maximum"),
+            ),
+            (
+                n.minimum,
+                n.exclusive_maximum,
+                ("minimum", "exclusiveMaximum"),
+            ),
+            (
+                n.exclusive_minimum,
+                n.exclusive_maximum,
+                ("exclusiveMinimum", "exclusiveMaximum"),
+            ),
+        ] {
+            // The exclusive limits cannot be equal to the other ones.
+            let exclusive =
+                keywords.0.starts_with("exclusive") || keywords.1.starts_with("exclusive");
+            check_range(*min, *max, exclusive, *keywords, &span, &mut errors);
+        }
+    }
+
+    if let Some(types) = instance_types(schema) {
+        if let Some(values) = &schema.enum_values {
+            for (i, value) in values.iter().enumerate() {
+                if !types.iter().any(|t| type_matches(*t, value)) {
+                    errors.0.push(
+                        Error::new(
+                            None,
+                            Some(span.clone() + "enum" + i),
+                            ErrorValue::InvalidSchema(InvalidSchema::TypeMismatch {
+                                keyword: "enum".into(),
+                                value: value.clone(),
+                            }),
+                        )
+                        .warning(),
+                    );
+                }
+            }
+        }
+
+        if let Some(value) = &schema.const_value {
+            if !types.iter().any(|t| type_matches(*t, value)) {
+                errors.0.push(
+                    Error::new(
+                        None,
+                        Some(span.clone() + "const"),
+                        ErrorValue::InvalidSchema(InvalidSchema::TypeMismatch {
+                            keyword: "const".into(),
+                            value: value.clone(),
+                        }),
+                    )
+                    .warning(),
+                );
+            }
+        }
+
+        if let Some(st) = &schema.string {
+            if !types.contains(&InstanceType::String) {
+                for (keyword, is_set) in &[
+                    ("pattern", st.pattern.is_some()),
+                    ("minLength", st.min_length.is_some()),
+                    ("maxLength", st.max_length.is_some()),
+                ] {
+                    if *is_set {
+                        errors.0.push(
+                            Error::new(
+                                None,
+                                Some(span.clone() + keyword),
+                                ErrorValue::InvalidSchema(InvalidSchema::IgnoredKeyword(
+                                    keyword.to_string(),
+                                )),
+                            )
+                            .warning(),
+                        );
+                    }
+                }
+            }
+        }
     }
 
     if let Some(st) = &schema.string {
+        check_range(
+            st.min_length.map(f64::from),
+            st.max_length.map(f64::from),
+            false,
+            ("minLength", "maxLength"),
+            &span,
+            &mut errors,
+        );
+
         if let Some(p) = &st.pattern {
             if let Err(error) = regex::Regex::new(&p) {
                 errors.0.push(Error::new(
@@ -170,10 +420,149 @@ fn verify_schema_object(
     }
 }
 
-fn local_definition(path: &str) -> Option<&str> {
-    if !path.starts_with("#/definitions/") {
-        return None;
+fn check_range(
+    min: Option<f64>,
+    max: Option<f64>,
+    exclusive: bool,
+    (min_keyword, max_keyword): (&str, &str),
+    span: &Keys,
+    errors: &mut Errors<Keys>,
+) {
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max || (exclusive && min == max) {
+            errors.0.push(Error::new(
+                None,
+                Some(span.clone() + min_keyword),
+                ErrorValue::InvalidSchema(InvalidSchema::EmptyRange {
+                    min: min_keyword.into(),
+                    max: max_keyword.into(),
+                }),
+            ));
+        }
+    }
+}
+
+fn instance_types(schema: &SchemaObject) -> Option<Vec<InstanceType>> {
+    match schema.instance_type.as_ref()? {
+        SingleOrVec::Single(t) => Some(vec![**t]),
+        SingleOrVec::Vec(types) => Some(types.clone()),
+    }
+}
+
+fn type_matches(instance_type: InstanceType, value: &Value) -> bool {
+    match (instance_type, value) {
+        (InstanceType::Null, Value::Null)
+        | (InstanceType::Boolean, Value::Bool(_))
+        | (InstanceType::Number, Value::Number(_))
+        | (InstanceType::String, Value::String(_))
+        | (InstanceType::Array, Value::Array(_))
+        | (InstanceType::Object, Value::Object(_)) => true,
+        (InstanceType::Integer, Value::Number(n)) => {
+            !n.is_f64() || matches!(n.as_f64(), Some(f) if f.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the types can have common values.
+fn types_overlap(a: &[InstanceType], b: &[InstanceType]) -> bool {
+    a.iter().any(|a| {
+        b.iter().any(|b| {
+            a == b
+                || matches!(
+                    (a, b),
+                    (InstanceType::Integer, InstanceType::Number)
+                        | (InstanceType::Number, InstanceType::Integer)
+                )
+        })
+    })
+}
+
+/// A `oneOf` subschema is unreachable if it never matches.
+fn unreachable_one_of(parent: &SchemaObject, schema: &Schema) -> bool {
+    let s = match schema {
+        Schema::Bool(allow) => return !*allow,
+        Schema::Object(s) => s,
+    };
+
+    match (instance_types(parent), instance_types(s)) {
+        (Some(parent_types), Some(types)) => !types_overlap(&parent_types, &types),
+        _ => false,
+    }
+}
+
+/// The definitions that always contain themselves.
+///
+/// A reference is a base case unless it is the schema itself, in `allOf`,
+/// in a required property or in an array that cannot be empty.
+fn ref_cycles(definitions: &Map<String, Schema>) -> Vec<&str> {
+    let refs: Map<&str, Vec<&str>> = definitions
+        .iter()
+        .map(|(name, schema)| {
+            let mut refs = Vec::new();
+            mandatory_refs(schema, &mut refs);
+            (name.as_str(), refs)
+        })
+        .collect();
+
+    refs.keys()
+        .copied()
+        .filter(|name| {
+            let mut visited = Vec::new();
+            let mut stack = refs[name].clone();
+
+            while let Some(next) = stack.pop() {
+                if next == *name {
+                    return true;
+                }
+
+                if !visited.contains(&next) {
+                    visited.push(next);
+                    stack.extend(refs.get(next).into_iter().flatten());
+                }
+            }
+
+            false
+        })
+        .collect()
+}
+
+fn mandatory_refs<'s>(schema: &'s Schema, refs: &mut Vec<&'s str>) {
+    let s = match schema {
+        Schema::Bool(_) => return,
+        Schema::Object(s) => s,
+    };
+
+    if let Some(name) = s.reference.as_deref().and_then(local_definition) {
+        refs.push(name);
+        return;
+    }
+
+    if let Some(all_of) = s.subschemas.as_ref().and_then(|sub| sub.all_of.as_ref()) {
+        for s in all_of {
+            mandatory_refs(s, refs);
+        }
+    }
+
+    if let Some(o) = &s.object {
+        for name in &o.required {
+            if let Some(s) = o.properties.get(name) {
+                mandatory_refs(s, refs);
+            }
+        }
     }
 
-    Some(path.trim_start_matches("#/definitions/"))
+    if let Some(a) = &s.array {
+        let min_items = a.min_items.unwrap_or(0) as usize;
+
+        match &a.items {
+            Some(SingleOrVec::Single(s)) if min_items > 0 => mandatory_refs(s, refs),
+            Some(SingleOrVec::Vec(items)) => {
+                for s in items.iter().take(min_items) {
+                    mandatory_refs(s, refs);
+                }
+            }
+            _ => {}
+        }
+    }
 }
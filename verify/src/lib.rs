@@ -33,7 +33,10 @@ There is official support only for [Schemars](https://github.com/GREsau/schemars
 
 This very basic example shows how to create a self-validating type with Verify and Schemars:
 
-```edition2018
+*/
+#![cfg_attr(feature = "schemars", doc = "```edition2018")]
+#![cfg_attr(not(feature = "schemars"), doc = "```ignore")]
+/*!
 # use schemars_crate::{self as schemars, JsonSchema};
 # use serde::Serialize;
 # use verify::Verify;
@@ -49,6 +52,9 @@ assert!(example.verify().is_ok());
 
 */
 
+pub mod adhoc;
+pub mod combinators;
+pub mod future;
 pub mod span;
 
 #[cfg(feature = "serde")]
@@ -60,6 +66,7 @@ mod impls;
 
 // "impls" is only for code structure, it is removed
 // for the public API.
+#[allow(unused_imports)]
 pub use impls::*;
 
 /**
@@ -79,7 +86,7 @@ Use [Serde](::serde) for validation.
 **Options:**
 
 - spans (optional): The name of the type that provides spans, it must implement [Spans](crate::serde::Spans).
-By default [KeySpans](crate::serde::KeySpans) is used.
+  By default [KeySpans](crate::serde::KeySpans) is used.
 
 **Example:**
 
@@ -94,6 +101,15 @@ Use [Schemars](::schemars_crate) schema for validation.
 Works only if the type implements [Serialize](serde::Serialize) and [JsonSchema](schemars_crate::JsonSchema).
 It also needs the `serde` to be enabled.
 
+The schema is only generated once for every type without generic parameters,
+see [cached_schema](crate::schemars::cached_schema).
+
+**Options:**
+
+- settings (optional): The settings used to generate the schema. It is either the name of the settings
+  provided by Schemars (`draft07`, `draft2019_09` or `openapi3`), or the path to a function
+  that returns [SchemaSettings](schemars_crate::gen::SchemaSettings). The default settings are used if not set.
+
 **Example:**
 
 ```ignore
@@ -101,6 +117,13 @@ It also needs the `serde` to be enabled.
 pub struct Example { ... }
 ```
 
+Or with options:
+
+```ignore
+#[verify(schemars(settings = "openapi3"), serde)]
+pub struct Example { ... }
+```
+
 ### verifier
 
 Provide what verifier to use.
@@ -110,7 +133,7 @@ Provide what verifier to use.
 - name: The name of the verifier type.
 - create (optional): How the verifier should be constructed, [Default](Default) is used if not set.
 - error (optional): The error type of the verifier, it might be needed when there are ambiguous complex generics
-that cannot be guessed by the macro.
+  that cannot be guessed by the macro.
 
 **Example:**
 
@@ -133,9 +156,186 @@ Or with options:
 pub struct Example { ... }
 ```
 
+### check
+
+A check for the whole value, it can be repeated. It is useful for rules
+that cannot be expressed by a schema, such as comparing two fields.
+
+The value must be the path to a function with the signature
+`fn(&Self) -> Result<(), E>`, where `E` is the error type of the verifier.
+The checks run after the verifier, and their errors are merged with the errors of the verifier.
+Errors for specific fields can be created with [SpannedError](crate::adhoc::SpannedError).
+
+**Example:**
+
+```ignore
+#[verify(schemars, serde, check = "start_before_end")]
+pub struct Example {
+    start: u32,
+    end: u32,
+}
+
+fn start_before_end(value: &Example) -> Result<(), Errors<Keys>> {
+    if value.start >= value.end {
+        return Err(Errors::spanned(
+            Some(Keys::from("end".to_string())),
+            "the end must be after the start".into(),
+        ));
+    }
+    Ok(())
+}
+```
+
+A check can also apply only to some groups, see the `group` option:
+
+```ignore
+#[verify(schemars, serde, group = "Group", check(path = "start_before_end", group = "Group::Create"))]
+pub struct Example { ... }
+```
+
+### group
+
+Implement [VerifyGroup](VerifyGroup) for the given group type alongside [Verify](Verify).
+
+Checks and field rules can be restricted to groups with patterns of the group type,
+for example `"Group::Create | Group::Update"`. The rules that are restricted to groups only run
+in [verify_group](VerifyGroup::verify_group) for the matching groups, everything else runs
+in both [verify](Verify::verify) and [verify_group](VerifyGroup::verify_group).
+
+It is not supported together with `context`.
+
+**Example:**
+
+```ignore
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde, group = "Group", partial = "Group::Update")]
+pub struct User {
+    #[verify(forbidden = "Group::Create", required = "Group::Update")]
+    id: Option<u64>,
+    ...
+}
+
+user.verify_group(Group::Create)?;
+```
+
+### partial
+
+A pattern of the group type, the `required` keywords of the schema are ignored
+for the matching groups. It can be repeated, and requires both `group` and `schemars`.
+
+**Example:**
+
+```ignore
+#[verify(schemars, serde, group = "Group", partial = "Group::Update")]
+pub struct Example { ... }
+```
+
+### context
+
+Implement [VerifyWith](VerifyWith) with the given context type instead of [Verify](Verify).
+
+The context is available as `ctx` in the `create` option of the verifier,
+and it is passed to all the `check_with` functions.
+
+**Example:**
+
+```ignore
+#[verify(schemars, serde, context = "Tenant")]
+pub struct Example { ... }
+
+example.verify_with(&tenant)?;
+```
+
+### check_with
+
+A check for the whole value that needs the context, it can be repeated.
+It requires the `context` option.
+
+The value must be the path to a function with the signature
+`fn(&Self, &Ctx) -> Result<(), E>`, where `E` is the error type of the verifier.
+The errors of the checks are merged with the errors of the verifier.
+
+**Example:**
+
+```ignore
+#[verify(schemars, serde, context = "Tenant", check_with = "discount_allowed")]
+pub struct Example { ... }
+
+fn discount_allowed(value: &Example, tenant: &Tenant) -> Result<(), Errors<Keys>> { ... }
+```
+
 ## Field Attributes
 
-Field attributes are ignored for now.
+### async_check
+
+An asynchronous check for the field, it can be repeated.
+If there is any, [AsyncVerify](crate::future::AsyncVerify) is also implemented for the type.
+
+The value must be the path to a function that takes a reference to the field and returns
+a future of `Result<(), E>` where `E: Display`. The error is reported with the span of the field,
+so the error type must implement [SpannedError](crate::adhoc::SpannedError) and the span type must
+implement `From<String>`.
+
+The checks only run if the synchronous validation succeeds, and all of them run concurrently.
+
+**Example:**
+
+```ignore
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde)]
+pub struct Example {
+    #[verify(async_check = "username_available")]
+    username: String,
+}
+
+async fn username_available(username: &String) -> Result<(), String> { ... }
+```
+
+### check_with
+
+A check for the field that needs the context, it can be repeated.
+It requires the `context` container option.
+
+The value must be the path to a function with the signature
+`fn(&Field, &Ctx) -> Result<(), E>` where `E: Display`. The errors are reported
+with the span of the field, the same way as for `async_check`.
+
+**Example:**
+
+```ignore
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde, context = "Tenant")]
+pub struct Example {
+    #[verify(check_with = "seats_within_limit")]
+    seats: u32,
+}
+
+fn seats_within_limit(seats: &u32, tenant: &Tenant) -> Result<(), String> { ... }
+```
+
+### required
+
+A pattern of the group type, the field must be set for the matching groups.
+The field must be an [Option](Option), and the container must have the `group` option.
+
+**Example:**
+
+```ignore
+#[verify(required = "Group::Update")]
+id: Option<u64>,
+```
+
+### forbidden
+
+A pattern of the group type, the field must not be set for the matching groups.
+The field must be an [Option](Option), and the container must have the `group` option.
+
+**Example:**
+
+```ignore
+#[verify(forbidden = "Group::Create")]
+id: Option<u64>,
+```
 
 */
 pub use verify_macros::Verify;
@@ -150,6 +350,32 @@ pub trait Error: Sized + std::error::Error + core::ops::AddAssign {
     fn custom<T: core::fmt::Display>(error: T) -> Self;
 }
 
+/// Errors that can be created from a message and the span of the invalid value.
+///
+/// It is required for errors returned by [AdHoc](crate::adhoc::AdHoc)
+/// and [Not](crate::combinators::Not).
+pub trait SpannedError<S: span::Span>: Error {
+    /// Create an error for the value at the given span.
+    fn spanned(span: Option<S>, message: String) -> Self;
+
+    /// Create an error for a required property that is missing.
+    ///
+    /// It is used by the validation groups of the derive macro.
+    fn missing_property(span: Option<S>, name: &str) -> Self {
+        Self::spanned(
+            span,
+            format!(r#"the required property "{}" is missing"#, name),
+        )
+    }
+
+    /// Create an error for a property that is present, but not allowed.
+    ///
+    /// It is used by the validation groups of the derive macro.
+    fn forbidden_property(span: Option<S>, name: &str) -> Self {
+        Self::spanned(span, format!(r#"the property "{}" is not allowed"#, name))
+    }
+}
+
 /// Convenience trait for interacting with errors.
 pub trait ErrorExt: Sized {
     /// Combine two error-like types. It is useful for
@@ -205,6 +431,22 @@ pub trait Verifier<S: span::Span> {
     }
 }
 
+impl<S: span::Span, T: ?Sized + Verifier<S>> Verifier<S> for &T {
+    type Error = T::Error;
+
+    fn verify_value<V: ?Sized + Validate<Span = S>>(&self, value: &V) -> Result<(), Self::Error> {
+        (**self).verify_value(value)
+    }
+
+    fn verify_value_with_span<V: ?Sized + Validate<Span = S>>(
+        &self,
+        value: &V,
+        span: Option<V::Span>,
+    ) -> Result<(), Self::Error> {
+        (**self).verify_value_with_span(value, span)
+    }
+}
+
 /// This trait is implemented by types that can validate themselves.
 pub trait Verify {
     /// The error returned by the validator.
@@ -214,6 +456,26 @@ pub trait Verify {
     fn verify(&self) -> Result<(), Self::Error>;
 }
 
+/// This trait is implemented by types that can validate themselves
+/// with a context, such as the role of the caller or feature flags.
+pub trait VerifyWith<Ctx: ?Sized> {
+    /// The error returned by the validator.
+    type Error: Error;
+
+    /// Validate self internally with the given context.
+    fn verify_with(&self, ctx: &Ctx) -> Result<(), Self::Error>;
+}
+
+/// This trait is implemented by types that are validated differently
+/// depending on a group, for example when they are created or updated.
+pub trait VerifyGroup<G> {
+    /// The error returned by the validator.
+    type Error: Error;
+
+    /// Validate self internally with the rules of the given group.
+    fn verify_group(&self, group: G) -> Result<(), Self::Error>;
+}
+
 /// Values that implement [Validate](Validate) can validate themselves against
 /// types that implement this trait.
 ///
@@ -368,19 +630,15 @@ pub trait ValidateMap<S: span::Span> {
         V: ?Sized + Validate<Span = S> + ToString;
 
     /// Validate a map entry.
-    fn validate_value<V: ?Sized>(&mut self, value: &V) -> Result<(), Self::Error>
+    fn validate_value<V>(&mut self, value: &V) -> Result<(), Self::Error>
     where
-        V: Validate<Span = S>;
+        V: ?Sized + Validate<Span = S>;
 
     /// Validate an entry (key and value).
-    fn validate_entry<K: ?Sized, V: ?Sized>(
-        &mut self,
-        key: &K,
-        value: &V,
-    ) -> Result<(), Self::Error>
+    fn validate_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
     where
-        K: Validate<Span = S>,
-        V: Validate<Span = S>,
+        K: ?Sized + Validate<Span = S>,
+        V: ?Sized + Validate<Span = S>,
     {
         self.validate_key(key)?;
         self.validate_value(value)
@@ -28,7 +28,7 @@ let result = validator.verify_value(&Spanned::new(&value, KeySpans::default());
 */
 
 use super::{
-    span::{Keys, Span, Spanned as SpannedTrait},
+    span::{Keys, Path, Segment, Span, Spanned as SpannedTrait},
     Validate, ValidateMap, ValidateSeq, Validator,
 };
 
@@ -201,6 +201,67 @@ impl Spans for KeySpans {
     }
 }
 
+/// PathSpans associates nested values with their full path
+/// from the first value as a [Path](crate::span::Path).
+///
+/// Unlike [KeySpans](KeySpans), sequence indices are kept as integers.
+///
+/// Keys that cannot be represented as strings will be replaced by `???`.
+#[derive(Default, Clone)]
+pub struct PathSpans {
+    is_seq: bool,
+    item_index: usize,
+}
+
+impl Spans for PathSpans {
+    type Span = Path;
+
+    fn key<S: ?Sized + Serialize>(&mut self, key: &S) -> NewSpan<Self::Span> {
+        let k = key
+            .serialize(KeySerializer)
+            .unwrap_or_else(|_| "???".to_string());
+
+        NewSpan::Add(Some(Segment::Key(k).into()))
+    }
+
+    fn value<S: ?Sized + Serialize>(&mut self, _value: &S) -> NewSpan<Self::Span> {
+        if self.is_seq {
+            let s = NewSpan::Add(Some(Segment::Index(self.item_index).into()));
+            self.item_index += 1;
+            return s;
+        }
+
+        NewSpan::NoChange
+    }
+
+    fn unit(&mut self) -> NewSpan<Self::Span> {
+        self.value(&())
+    }
+
+    fn map_start(&mut self) -> NewSpan<Self::Span> {
+        NewSpan::NoChange
+    }
+
+    fn map_end(&mut self) -> NewSpan<Self::Span> {
+        NewSpan::Add(None)
+    }
+
+    fn seq_start(&mut self) -> NewSpan<Self::Span> {
+        self.is_seq = true;
+        NewSpan::NoChange
+    }
+
+    fn seq_end(&mut self) -> NewSpan<Self::Span> {
+        self.is_seq = false;
+        self.item_index = 0;
+        NewSpan::Add(None)
+    }
+
+    fn descend(&self) -> Self {
+        Self::default()
+    }
+}
+
 struct Hashed<'a, S: ?Sized + Serialize>(&'a S);
 
 impl<'a, S: ?Sized + Serialize> Hashed<'a, S> {
@@ -115,7 +115,7 @@ impl Keys {
 
 impl AddAssign for Keys {
     fn add_assign(&mut self, rhs: Self) {
-        self.0.extend(rhs.0.into_iter())
+        self.0.extend(rhs.0)
     }
 }
 
@@ -148,3 +148,541 @@ impl IntoIterator for Keys {
         self.0.into_iter()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keys {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keys {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<String>::deserialize(deserializer).map(|v| Keys(v.into_iter().collect()))
+    }
+}
+
+/// A segment of a [Path](Path).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Segment {
+    /// A key of a map.
+    Key(String),
+    /// An index of a sequence.
+    Index(usize),
+}
+
+impl From<String> for Segment {
+    fn from(key: String) -> Self {
+        Segment::Key(key)
+    }
+}
+
+impl From<&str> for Segment {
+    fn from(key: &str) -> Self {
+        Segment::Key(key.to_string())
+    }
+}
+
+impl From<usize> for Segment {
+    fn from(index: usize) -> Self {
+        Segment::Index(index)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+type PathSmallVecArray = [Segment; 10];
+
+#[cfg(feature = "smallvec")]
+type PathInner = smallvec_crate::SmallVec<PathSmallVecArray>;
+
+#[cfg(not(feature = "smallvec"))]
+type PathInner = Vec<Segment>;
+
+/// A span consisting of map keys and sequence indices.
+///
+/// Unlike [Keys](Keys), the key `"0"` and the index `0` are different,
+/// and the path can be rendered in multiple forms:
+///
+/// | Form                                    | Example              |
+/// |-----------------------------------------|----------------------|
+/// | [JSON Pointer](Path::json_pointer)      | `/items/0/a.b`       |
+/// | [JSONPath](Path::json_path)             | `$.items[0]['a.b']`  |
+/// | [Dotted](Path::dotted)                  | `items[0].a\.b`      |
+///
+/// All of them can be parsed back, but JSON Pointers cannot tell indices
+/// and keys apart, so segments that are valid array indices become [indices](Segment::Index).
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct Path(PathInner);
+
+impl Span for Path {}
+
+impl Path {
+    /// Create a new instance with no segments.
+    pub fn new() -> Self {
+        Path(PathInner::new())
+    }
+
+    /// Iterator over the segments.
+    pub fn iter(&self) -> impl Iterator<Item = &Segment> {
+        self.0.iter()
+    }
+
+    /// Add a new segment.
+    pub fn push(&mut self, segment: impl Into<Segment>) {
+        self.0.push(segment.into())
+    }
+
+    /// Remove the last segment.
+    pub fn pop(&mut self) -> Option<Segment> {
+        self.0.pop()
+    }
+
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the path points to the root value.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Render the path as a JSON Pointer (RFC 6901), e.g. `/items/0/name`.
+    pub fn json_pointer(&self) -> String {
+        let mut s = String::new();
+
+        for segment in self.iter() {
+            s.push('/');
+            match segment {
+                Segment::Key(k) => s.push_str(&k.replace('~', "~0").replace('/', "~1")),
+                Segment::Index(i) => s.push_str(&i.to_string()),
+            }
+        }
+
+        s
+    }
+
+    /// Render the path as a JSONPath expression, e.g. `$.items[0].name`.
+    ///
+    /// Keys that are not identifiers are quoted, e.g. `$['first name']`.
+    pub fn json_path(&self) -> String {
+        let mut s = String::from("$");
+
+        for segment in self.iter() {
+            match segment {
+                Segment::Key(k) if is_identifier(k) => {
+                    s.push('.');
+                    s.push_str(k);
+                }
+                Segment::Key(k) => {
+                    s.push_str("['");
+                    for c in k.chars() {
+                        if c == '\'' || c == '\\' {
+                            s.push('\\');
+                        }
+                        s.push(c);
+                    }
+                    s.push_str("']");
+                }
+                Segment::Index(i) => {
+                    s.push('[');
+                    s.push_str(&i.to_string());
+                    s.push(']');
+                }
+            }
+        }
+
+        s
+    }
+
+    /// Render the path with keys joined by dots and indices in brackets,
+    /// e.g. `items[0].name`.
+    ///
+    /// Dots, brackets and backslashes in keys are escaped with a backslash.
+    pub fn dotted(&self) -> String {
+        let mut s = String::new();
+
+        for (i, segment) in self.iter().enumerate() {
+            match segment {
+                Segment::Key(k) => {
+                    if i != 0 {
+                        s.push('.');
+                    }
+                    for c in k.chars() {
+                        if matches!(c, '.' | '[' | ']' | '\\') {
+                            s.push('\\');
+                        }
+                        s.push(c);
+                    }
+                }
+                Segment::Index(i) => {
+                    s.push('[');
+                    s.push_str(&i.to_string());
+                    s.push(']');
+                }
+            }
+        }
+
+        s
+    }
+
+    /// Parse a JSON Pointer, read more [here](Path).
+    pub fn from_json_pointer(pointer: &str) -> Result<Self, ParsePathError> {
+        let mut path = Path::new();
+
+        if pointer.is_empty() {
+            return Ok(path);
+        }
+
+        if !pointer.starts_with('/') {
+            return Err(ParsePathError::new(0, "expected '/'"));
+        }
+
+        let mut position = 1;
+
+        for token in pointer[1..].split('/') {
+            if let Some(i) = token.find('~') {
+                if !token[i..]
+                    .split('~')
+                    .skip(1)
+                    .all(|s| s.starts_with('0') || s.starts_with('1'))
+                {
+                    return Err(ParsePathError::new(position + i, "invalid escape"));
+                }
+            }
+
+            match parse_index(token) {
+                Some(index) => path.push(index),
+                None => path.push(token.replace("~1", "/").replace("~0", "~")),
+            }
+
+            position += token.len() + 1;
+        }
+
+        Ok(path)
+    }
+
+    /// Parse a JSONPath expression that was rendered by [json_path](Path::json_path).
+    ///
+    /// Only names, indices and quoted names are supported,
+    /// e.g. `$.items[0]["first name"]`.
+    pub fn from_json_path(json_path: &str) -> Result<Self, ParsePathError> {
+        let mut path = Path::new();
+
+        let mut rest = json_path
+            .strip_prefix('$')
+            .ok_or_else(|| ParsePathError::new(0, "expected '$'"))?;
+
+        while !rest.is_empty() {
+            let position = json_path.len() - rest.len();
+
+            if let Some(r) = rest.strip_prefix('.') {
+                let end = r.find(['.', '[']).unwrap_or(r.len());
+
+                if end == 0 {
+                    return Err(ParsePathError::new(position + 1, "expected a name"));
+                }
+
+                path.push(&r[..end]);
+                rest = &r[end..];
+            } else if let Some(r) = rest.strip_prefix('[') {
+                match r.chars().next() {
+                    Some(quote) if quote == '\'' || quote == '"' => {
+                        let (key, len) = unquote(&r[1..], quote).ok_or_else(|| {
+                            ParsePathError::new(json_path.len(), "unterminated string")
+                        })?;
+
+                        path.push(key);
+                        rest = r[1 + len..].strip_prefix(']').ok_or_else(|| {
+                            ParsePathError::new(position + 2 + len, "expected ']'")
+                        })?;
+                    }
+                    _ => {
+                        let (index, r) = bracket_index(r, position + 1)?;
+                        path.push(index);
+                        rest = r;
+                    }
+                }
+            } else {
+                return Err(ParsePathError::new(position, "expected '.' or '['"));
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Parse a path that was rendered by [dotted](Path::dotted).
+    pub fn from_dotted(dotted: &str) -> Result<Self, ParsePathError> {
+        let mut path = Path::new();
+
+        if dotted.is_empty() {
+            return Ok(path);
+        }
+
+        let mut rest = dotted;
+        let mut key_next = !rest.starts_with('[');
+
+        loop {
+            let position = dotted.len() - rest.len();
+
+            if key_next {
+                let mut key = String::new();
+                let mut end = rest.len();
+                let mut chars = rest.char_indices();
+
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some((_, c)) => key.push(c),
+                            None => {
+                                return Err(ParsePathError::new(position + i, "invalid escape"))
+                            }
+                        },
+                        '.' | '[' => {
+                            end = i;
+                            break;
+                        }
+                        ']' => return Err(ParsePathError::new(position + i, "unexpected ']'")),
+                        c => key.push(c),
+                    }
+                }
+
+                path.push(key);
+                rest = &rest[end..];
+            } else {
+                let (index, r) = bracket_index(&rest[1..], position + 1)?;
+                path.push(index);
+                rest = r;
+            }
+
+            match rest.chars().next() {
+                None => break,
+                Some('.') => {
+                    rest = &rest[1..];
+                    key_next = true;
+                }
+                Some('[') => key_next = false,
+                Some(_) => {
+                    return Err(ParsePathError::new(
+                        dotted.len() - rest.len(),
+                        "expected '.' or '['",
+                    ))
+                }
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Return the inner container.
+    pub fn into_inner(self) -> PathInner {
+        self.0
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse an index after an opening bracket,
+/// and return the rest after the closing bracket.
+fn bracket_index(s: &str, position: usize) -> Result<(usize, &str), ParsePathError> {
+    let end = s
+        .find(']')
+        .ok_or_else(|| ParsePathError::new(position + s.len(), "expected ']'"))?;
+
+    let index =
+        parse_index(&s[..end]).ok_or_else(|| ParsePathError::new(position, "expected an index"))?;
+
+    Ok((index, &s[end + 1..]))
+}
+
+/// Read a quoted string after the opening quote,
+/// and return it with the length including the closing quote.
+fn unquote(s: &str, quote: char) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?.1),
+            c if c == quote => return Some((value, i + c.len_utf8())),
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+/// Parse an array index without leading zeros.
+fn parse_index(s: &str) -> Option<usize> {
+    if s.is_empty() || (s.len() > 1 && s.starts_with('0')) || !s.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+impl core::fmt::Display for Path {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.dotted())
+    }
+}
+
+impl AddAssign for Path {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.extend(rhs.0)
+    }
+}
+
+impl<S: Into<Segment>> Add<S> for Path {
+    type Output = Self;
+    fn add(mut self, rhs: S) -> Self::Output {
+        self.push(rhs);
+        self
+    }
+}
+
+impl From<Segment> for Path {
+    fn from(segment: Segment) -> Self {
+        let mut p = Self::new();
+        p.push(segment);
+        p
+    }
+}
+
+impl From<Keys> for Path {
+    /// Every key becomes a [Key](Segment::Key), the indices cannot be recovered.
+    fn from(keys: Keys) -> Self {
+        Path(keys.into_iter().map(Segment::Key).collect())
+    }
+}
+
+impl core::iter::FromIterator<Segment> for Path {
+    fn from_iter<T: IntoIterator<Item = Segment>>(iter: T) -> Self {
+        Path(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Path {
+    type Item = Segment;
+
+    #[cfg(feature = "smallvec")]
+    type IntoIter = smallvec_crate::IntoIter<PathSmallVecArray>;
+
+    #[cfg(not(feature = "smallvec"))]
+    type IntoIter = std::vec::IntoIter<Segment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Path {
+    /// Find the value the path points to.
+    ///
+    /// [Indices](Segment::Index) are also looked up as keys in objects,
+    /// so paths parsed from JSON Pointers work as well.
+    pub fn resolve<'v>(&self, value: &'v serde_json::Value) -> Option<&'v serde_json::Value> {
+        self.iter()
+            .try_fold(value, |value, segment| match (segment, value) {
+                (Segment::Key(k), serde_json::Value::Object(obj)) => obj.get(k),
+                (Segment::Index(i), serde_json::Value::Array(arr)) => arr.get(*i),
+                (Segment::Index(i), serde_json::Value::Object(obj)) => obj.get(&i.to_string()),
+                _ => None,
+            })
+    }
+
+    /// Find the value the path points to, and return a mutable reference to it.
+    pub fn resolve_mut<'v>(
+        &self,
+        value: &'v mut serde_json::Value,
+    ) -> Option<&'v mut serde_json::Value> {
+        self.iter()
+            .try_fold(value, |value, segment| match (segment, value) {
+                (Segment::Key(k), serde_json::Value::Object(obj)) => obj.get_mut(k),
+                (Segment::Index(i), serde_json::Value::Array(arr)) => arr.get_mut(*i),
+                (Segment::Index(i), serde_json::Value::Object(obj)) => obj.get_mut(&i.to_string()),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Segment {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Segment::Key(k) => serializer.serialize_str(k),
+            Segment::Index(i) => serializer.serialize_u64(*i as u64),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Segment {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Key(String),
+            Index(usize),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Key(k) => Segment::Key(k),
+            Repr::Index(i) => Segment::Index(i),
+        })
+    }
+}
+
+/// A path is serialized as an array of strings and integers.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Path {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Path {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Segment>::deserialize(deserializer).map(|v| v.into_iter().collect())
+    }
+}
+
+/// Error returned when a [Path](Path) cannot be parsed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParsePathError {
+    position: usize,
+    reason: &'static str,
+}
+
+impl ParsePathError {
+    fn new(position: usize, reason: &'static str) -> Self {
+        Self { position, reason }
+    }
+
+    /// The byte offset of the error in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl core::fmt::Display for ParsePathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid path at {}: {}", self.position, self.reason)
+    }
+}
+
+impl std::error::Error for ParsePathError {}
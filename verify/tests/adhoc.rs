@@ -0,0 +1,93 @@
+use schemars_crate::schema::RootSchema;
+use serde_json::json;
+use verify::{
+    adhoc::{AdHoc, Failures, Peek},
+    combinators::VerifierExt,
+    schemars::errors::Errors,
+    serde::{KeySpans, Spanned},
+    span::Keys,
+    Verifier,
+};
+
+#[test]
+fn test_adhoc() {
+    let verifier: AdHoc<Keys> = AdHoc::new()
+        .on_str(|s| {
+            if s.trim().is_empty() {
+                return Err("blank strings are not allowed".into());
+            }
+            Ok(())
+        })
+        .on_map_entry(|key, value| match (key, value) {
+            ("port", Peek::U64(0)) => Err("the port must not be 0".into()),
+            _ => Ok(()),
+        })
+        .on_seq(|len| {
+            if len > 2 {
+                return Err("too many hosts".into());
+            }
+            Ok(())
+        });
+
+    let valid = json!({
+        "port": 8080,
+        "hosts": ["a", "b"]
+    });
+
+    assert!(verifier
+        .verify_value(&Spanned::new(&valid, KeySpans::default()))
+        .is_ok());
+
+    let invalid = json!({
+        "port": 0,
+        "hosts": ["a", " ", "c"]
+    });
+
+    let Failures(failures) = verifier
+        .verify_value(&Spanned::new(&invalid, KeySpans::default()))
+        .unwrap_err();
+
+    let mut failures: Vec<_> = failures
+        .iter()
+        .map(|f| (f.span.as_ref().map(Keys::dotted), f.message.as_str()))
+        .collect();
+    failures.sort();
+
+    assert_eq!(
+        failures,
+        [
+            // KeySpans provides no span after the end of sequences.
+            (None, "too many hosts"),
+            (Some("hosts.1".to_string()), "blank strings are not allowed"),
+            (Some("port".to_string()), "the port must not be 0"),
+        ]
+    );
+}
+
+#[test]
+fn test_adhoc_with_schema() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "properties": { "name": { "type": "string", "maxLength": 3 } }
+    }))
+    .unwrap();
+
+    let no_admin: AdHoc<Keys, Errors<Keys>> = AdHoc::new().on_str(|s| {
+        if s == "root" {
+            return Err("reserved name".into());
+        }
+        Ok(())
+    });
+
+    let value = json!({ "name": "root" });
+
+    let errors = (&schema)
+        .and(no_admin)
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|e| e.span.as_ref().unwrap().dotted() == "name"));
+}
@@ -0,0 +1,226 @@
+use schemars_crate::{self as schemars, JsonSchema};
+use serde::Serialize;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+use verify::{
+    future::{self, AsyncVerify},
+    schemars::errors::Errors,
+    span::Keys,
+    SpannedError, Verify,
+};
+
+/// A minimal executor for the tests.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Yields to the executor the given number of times.
+struct YieldNow(usize);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 == 0 {
+            return Poll::Ready(());
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// An in-memory stand-in for a user service.
+static TAKEN: &[&str] = &["admin", "root"];
+
+/// The order of the lookups.
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn is_taken(name: &str) -> bool {
+    TAKEN.contains(&name)
+}
+
+async fn username_available(name: &String) -> Result<(), String> {
+    LOG.lock().unwrap().push(format!("start {}", name));
+    YieldNow(1).await;
+    LOG.lock().unwrap().push(format!("end {}", name));
+
+    if is_taken(name) {
+        return Err(format!(r#"the username "{}" is taken"#, name));
+    }
+    Ok(())
+}
+
+async fn referrer_exists(name: &Option<String>) -> Result<(), String> {
+    let name = match name {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+
+    LOG.lock().unwrap().push(format!("start {}", name));
+    YieldNow(1).await;
+    LOG.lock().unwrap().push(format!("end {}", name));
+
+    if !is_taken(name) {
+        return Err(format!(r#"the user "{}" does not exist"#, name));
+    }
+    Ok(())
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde)]
+struct SignUp {
+    #[verify(async_check = "username_available")]
+    #[schemars(length(min = 3))]
+    username: String,
+
+    #[serde(rename = "referredBy")]
+    #[verify(async_check = "referrer_exists")]
+    referred_by: Option<String>,
+}
+
+#[test]
+fn test_async_verify() {
+    let sign_up = SignUp {
+        username: "alice".into(),
+        referred_by: Some("admin".into()),
+    };
+    assert!(block_on(sign_up.verify_async()).is_ok());
+
+    LOG.lock().unwrap().clear();
+
+    let sign_up = SignUp {
+        username: "root".into(),
+        referred_by: Some("bob".into()),
+    };
+
+    let errors = block_on(sign_up.verify_async()).unwrap_err();
+
+    // The checks run concurrently.
+    assert_eq!(
+        *LOG.lock().unwrap(),
+        ["start root", "start bob", "end root", "end bob"]
+    );
+
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|e| (e.span.as_ref().unwrap().dotted(), e.to_string()))
+        .collect();
+
+    // The errors are in the order of the fields.
+    assert_eq!(
+        errors,
+        [
+            (
+                "username".to_string(),
+                r#"the username "root" is taken"#.to_string()
+            ),
+            (
+                "referredBy".to_string(),
+                r#"the user "bob" does not exist"#.to_string()
+            ),
+        ]
+    );
+
+    // The async checks only run if the value is valid otherwise.
+    LOG.lock().unwrap().clear();
+
+    let sign_up = SignUp {
+        username: "al".into(),
+        referred_by: None,
+    };
+
+    let errors = block_on(sign_up.verify_async()).unwrap_err();
+    assert_eq!(errors.iter().next().unwrap().value.code(), "min_length");
+    assert!(LOG.lock().unwrap().is_empty());
+}
+
+async fn invitee_available(name: &String) -> Result<(), String> {
+    if is_taken(name) {
+        return Err(format!(r#"the username "{}" is taken"#, name));
+    }
+    Ok(())
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde)]
+struct Invite<T> {
+    #[verify(async_check = "invitee_available")]
+    username: String,
+
+    #[schemars(range(max = 10))]
+    seats: T,
+}
+
+#[test]
+fn test_async_verify_generic() {
+    let invite = Invite {
+        username: "alice".to_string(),
+        seats: 2u8,
+    };
+    assert!(block_on(invite.verify_async()).is_ok());
+
+    let invite = Invite {
+        username: "admin".to_string(),
+        seats: 2u8,
+    };
+    let errors = block_on(invite.verify_async()).unwrap_err();
+    assert_eq!(
+        errors.iter().next().unwrap().to_string(),
+        r#"the username "admin" is taken"#
+    );
+
+    let invite = Invite {
+        username: "alice".to_string(),
+        seats: 20u8,
+    };
+    let errors = block_on(invite.verify_async()).unwrap_err();
+    assert_eq!(errors.iter().next().unwrap().value.code(), "maximum");
+}
+
+#[test]
+fn test_all_order() {
+    async fn check(name: &str, yields: usize) -> Result<(), Errors<Keys>> {
+        YieldNow(yields).await;
+        Err(Errors::spanned(
+            Some(Keys::new() + name),
+            "invalid".to_string(),
+        ))
+    }
+
+    // The first check finishes last, but its errors come first.
+    let errors = block_on(future::all(vec![
+        Box::pin(check("first", 3)),
+        Box::pin(check("second", 0)),
+        Box::pin(check("third", 1)),
+    ]))
+    .unwrap_err();
+
+    let spans: Vec<_> = errors
+        .iter()
+        .map(|e| e.span.as_ref().unwrap().dotted())
+        .collect();
+    assert_eq!(spans, ["first", "second", "third"]);
+}
@@ -0,0 +1,82 @@
+use schemars_crate::schema::RootSchema;
+use serde_json::json;
+use verify::{
+    combinators::{Both, Not, VerifierExt},
+    schemars::errors::Errors,
+    serde::{KeySpans, Spanned},
+    span::Keys,
+    Verifier,
+};
+
+fn schema(value: serde_json::Value) -> RootSchema {
+    serde_json::from_value(value).unwrap()
+}
+
+#[derive(Debug)]
+struct ErrorCount(usize);
+
+impl std::fmt::Display for ErrorCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} errors", self.0)
+    }
+}
+
+impl std::error::Error for ErrorCount {}
+
+impl std::ops::AddAssign for ErrorCount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl verify::Error for ErrorCount {
+    fn custom<T: std::fmt::Display>(_error: T) -> Self {
+        ErrorCount(1)
+    }
+}
+
+#[test]
+fn test_combinators() {
+    let short = schema(json!({ "type": "string", "maxLength": 3 }));
+    let lower = schema(json!({ "type": "string", "pattern": "^[a-z]*$" }));
+    let number = schema(json!({ "type": "number" }));
+
+    let value = json!("ABCD");
+    let value = Spanned::new(&value, KeySpans::default());
+
+    let errors: Errors<Keys> = (&short).and(&lower).verify_value(&value).unwrap_err();
+    assert_eq!(errors.len(), 2);
+
+    assert!(Not(Both(&short, &lower)).verify_value(&value).is_ok());
+    assert!((&number).or(&short).verify_value(&value).is_err());
+
+    let count = (&short)
+        .and(&lower)
+        .map_err(|e: Errors<Keys>| ErrorCount(e.len()))
+        .verify_value(&value)
+        .unwrap_err();
+    assert_eq!(count.0, 2);
+
+    let value = json!("abc");
+    let value = Spanned::new(&value, KeySpans::default());
+
+    assert!((&short).and(&lower).verify_value(&value).is_ok());
+    assert!((&number).or(&short).verify_value(&value).is_ok());
+    assert!((&number).not().verify_value(&value).is_ok());
+    assert!((&short).not().verify_value(&value).is_err());
+}
+
+#[test]
+fn test_not_span() {
+    let short = schema(json!({ "type": "string", "maxLength": 3 }));
+
+    let value = json!("abc");
+    let value = Spanned::new(&value, KeySpans::default());
+
+    let errors: Errors<Keys> = Not(&short)
+        .verify_value_with_span(&value, Some(Keys::new() + "name"))
+        .unwrap_err();
+
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "name");
+}
@@ -0,0 +1,120 @@
+use schemars_crate::{self as schemars, JsonSchema};
+use serde::Serialize;
+use verify::{
+    adhoc::{AdHoc, SpannedError},
+    schemars::errors::Errors,
+    span::Keys,
+    Verify, VerifyWith,
+};
+
+struct Tenant {
+    max_seats: u32,
+    admin: bool,
+}
+
+impl Tenant {
+    fn name_rules(&self) -> AdHoc<Keys> {
+        let admin = self.admin;
+        AdHoc::new().on_str(move |s| {
+            if !admin && s.starts_with('_') {
+                return Err("only admins can use reserved names".into());
+            }
+            Ok(())
+        })
+    }
+}
+
+fn seats_within_limit(seats: &u32, tenant: &Tenant) -> Result<(), String> {
+    if *seats > tenant.max_seats {
+        return Err(format!("at most {} seats are allowed", tenant.max_seats));
+    }
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn discount_allowed(order: &Order, tenant: &Tenant) -> Result<(), Errors<Keys>> {
+    if order.discount > 0 && !tenant.admin {
+        return Err(Errors::spanned(
+            Some(Keys::from("discount".to_string())),
+            "only admins can give discounts".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde, context = "Tenant", check_with = "discount_allowed")]
+struct Order {
+    #[verify(check_with = "seats_within_limit")]
+    #[schemars(range(min = 1))]
+    seats: u32,
+    discount: u32,
+}
+
+#[derive(Verify, Serialize)]
+#[verify(
+    serde,
+    context = "Tenant",
+    verifier(name = "AdHoc<Keys>", create = "ctx.name_rules()")
+)]
+struct Team {
+    name: String,
+}
+
+#[test]
+fn test_verify_with() {
+    let admin = Tenant {
+        max_seats: 10,
+        admin: true,
+    };
+    let user = Tenant {
+        max_seats: 2,
+        admin: false,
+    };
+
+    let order = Order {
+        seats: 5,
+        discount: 10,
+    };
+
+    assert!(order.verify_with(&admin).is_ok());
+
+    let errors = order.verify_with(&user).unwrap_err();
+
+    let mut errors: Vec<_> = errors
+        .iter()
+        .map(|e| (e.span.as_ref().unwrap().dotted(), e.to_string()))
+        .collect();
+    errors.sort();
+
+    assert_eq!(
+        errors,
+        [
+            (
+                "discount".to_string(),
+                "only admins can give discounts".to_string()
+            ),
+            (
+                "seats".to_string(),
+                "at most 2 seats are allowed".to_string()
+            ),
+        ]
+    );
+
+    // The checks are merged with the schema errors.
+    let order = Order {
+        seats: 0,
+        discount: 0,
+    };
+    assert_eq!(order.verify_with(&user).unwrap_err().len(), 1);
+
+    let team = Team {
+        name: "_core".into(),
+    };
+
+    assert!(team.verify_with(&admin).is_ok());
+    assert_eq!(
+        team.verify_with(&user).unwrap_err().0[0].message,
+        "only admins can use reserved names"
+    );
+}
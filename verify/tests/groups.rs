@@ -0,0 +1,128 @@
+use schemars_crate::{self as schemars, JsonSchema};
+use serde::Serialize;
+use verify::{adhoc::SpannedError, schemars::errors::Errors, span::Keys, Verify, VerifyGroup};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Group {
+    Create,
+    Update,
+}
+
+#[derive(Default, Verify, Serialize, JsonSchema)]
+#[verify(
+    schemars,
+    serde,
+    group = "Group",
+    partial = "Group::Update",
+    check(path = "password_not_name", group = "Group::Create")
+)]
+struct User {
+    #[verify(forbidden = "Group::Create", required = "Group::Update")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+
+    #[schemars(required)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    #[verify(required = "Group::Create")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+#[allow(clippy::result_large_err)]
+fn password_not_name(user: &User) -> Result<(), Errors<Keys>> {
+    if user.password.is_some() && user.password == user.name {
+        return Err(Errors::spanned(
+            Some(Keys::from("password".to_string())),
+            "the password must not be the name".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn error_spans(errors: Errors<Keys>) -> Vec<String> {
+    let mut spans: Vec<_> = errors
+        .iter()
+        .map(|e| e.span.as_ref().map(Keys::dotted).unwrap_or_default())
+        .collect();
+    spans.sort();
+    spans
+}
+
+#[test]
+fn test_groups() {
+    let user = User {
+        name: Some("someone".into()),
+        password: Some("secret".into()),
+        ..Default::default()
+    };
+    assert!(user.verify_group(Group::Create).is_ok());
+
+    let user = User {
+        id: Some(1),
+        name: None,
+        password: Some("secret".into()),
+    };
+    assert_eq!(
+        error_spans(user.verify_group(Group::Create).unwrap_err()),
+        ["", "id"]
+    );
+
+    let user = User {
+        name: Some("someone".into()),
+        password: Some("someone".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        error_spans(user.verify_group(Group::Create).unwrap_err()),
+        ["password"]
+    );
+    // Group checks do not run for other groups or without a group.
+    assert!(user.verify().is_ok());
+
+    // The schema is partial for updates.
+    let user = User {
+        id: Some(1),
+        ..Default::default()
+    };
+    assert!(user.verify_group(Group::Update).is_ok());
+    assert_eq!(error_spans(user.verify().unwrap_err()), [""]);
+
+    let user = User {
+        name: Some("someone".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        error_spans(user.verify_group(Group::Update).unwrap_err()),
+        ["id"]
+    );
+}
+
+#[test]
+fn test_group_error_codes() {
+    let user = User {
+        id: Some(1),
+        name: Some("someone".into()),
+        ..Default::default()
+    };
+    let errors = user.verify_group(Group::Create).unwrap_err();
+    let mut codes: Vec<_> = errors
+        .iter()
+        .map(|e| (e.value.code(), e.value.to_string()))
+        .collect();
+    codes.sort();
+    assert_eq!(
+        codes,
+        [
+            (
+                "forbidden",
+                r#"the property "id" is not allowed"#.to_string()
+            ),
+            (
+                "required",
+                r#"the required property "password" is missing"#.to_string()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,67 @@
+use serde_json::json;
+use verify::{
+    schemars::{errors::Errors, infer},
+    serde::{KeySpans, Spanned},
+    span::Keys,
+    Verifier,
+};
+
+#[test]
+fn test_infer() {
+    let samples = vec![
+        json!({ "id": 1, "status": "active", "tags": ["a"], "score": 1.5, "note": null }),
+        json!({ "id": 2, "status": "active", "tags": [], "score": 2 }),
+        json!({ "id": 3, "status": "deleted", "tags": ["b", "c"], "note": "x", "owner": { "name": "n" } }),
+        json!({ "id": 4, "status": "deleted", "tags": ["d"], "note": "y", "owner": { "name": "m" } }),
+    ];
+
+    let schema = infer::infer(&samples);
+
+    assert_eq!(
+        serde_json::to_value(&schema.schema).unwrap(),
+        json!({
+            "type": "object",
+            "required": ["id", "status", "tags"],
+            "properties": {
+                "id": { "type": "integer" },
+                "note": { "type": ["null", "string"] },
+                "owner": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } }
+                },
+                "score": { "type": "number" },
+                "status": { "type": "string", "enum": ["active", "deleted"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        })
+    );
+
+    for sample in &samples {
+        assert!(schema
+            .verify_value(&Spanned::new(sample, KeySpans::default()))
+            .is_ok());
+    }
+
+    let value = json!({ "id": "5", "status": "archived", "tags": [1] });
+    let errors: Errors<Keys> = schema
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    assert_eq!(errors.len(), 3);
+
+    let strict = infer::Inference::new()
+        .max_enum_values(0)
+        .deny_unknown(true)
+        .samples(&samples)
+        .root_schema();
+
+    let value = json!({ "id": 5, "status": "archived", "tags": [], "extra": true });
+    let errors: Errors<Keys> = strict
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors.iter().next().unwrap().value.code(),
+        "unknown_property"
+    );
+}
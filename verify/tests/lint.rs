@@ -0,0 +1,163 @@
+use schemars_crate::{self as schemars, schema::RootSchema, JsonSchema};
+use serde_json::json;
+use verify::{
+    schemars::{errors::ErrorValue, lint},
+    Verify,
+};
+
+/// The findings as "span: code", sorted by the span.
+fn findings(schema: &RootSchema) -> Vec<String> {
+    let mut findings = lint(schema)
+        .iter()
+        .map(|e| {
+            let code = match &e.value {
+                ErrorValue::InvalidSchema(err) => serde_json::to_value(err).unwrap()["code"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+                other => other.code().to_string(),
+            };
+            let severity = if e.is_warning() { " (warning)" } else { "" };
+
+            format!(
+                "{}: {}{}",
+                e.span.as_ref().unwrap().dotted(),
+                code,
+                severity
+            )
+        })
+        .collect::<Vec<_>>();
+    findings.sort();
+    findings
+}
+
+#[test]
+fn test_lint() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "required": ["name", "extra"],
+        "additionalProperties": false,
+        "properties": {
+            "name": { "type": "string", "minLength": 5, "maxLength": 2 },
+            "age": { "type": "integer", "minimum": 10, "exclusiveMaximum": 10, "pattern": "^a" },
+            "kind": { "type": "string", "enum": ["a", 1] },
+            "items": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "oneOf": [
+                        { "type": "object", "required": ["a"] },
+                        { "type": "string" },
+                        { "type": "object", "required": ["a"] }
+                    ]
+                }
+            },
+            "node": { "$ref": "#/definitions/Node" },
+            "list": { "$ref": "#/definitions/List" }
+        },
+        "definitions": {
+            "Node": {
+                "type": "object",
+                "required": ["next"],
+                "properties": { "next": { "$ref": "#/definitions/Node" } }
+            },
+            "List": {
+                "type": "object",
+                "properties": { "next": { "$ref": "#/definitions/List" } }
+            }
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        findings(&schema),
+        vec![
+            "definitions.Node: ref_cycle",
+            "properties.age.minimum: empty_range",
+            "properties.age.pattern: ignored_keyword (warning)",
+            "properties.items.items.oneOf.0: duplicate_one_of (warning)",
+            "properties.items.items.oneOf.1: unreachable_one_of (warning)",
+            "properties.items.items.oneOf.2: duplicate_one_of (warning)",
+            "properties.kind.enum.1: type_mismatch (warning)",
+            "properties.name.minLength: empty_range",
+            "required: required_not_allowed",
+        ]
+    );
+
+    // Only invalid references and patterns make the schema invalid.
+    assert!(schema.verify().is_ok());
+
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "properties": {
+            "node": { "$ref": "#/definitions/Node" },
+            "name": { "type": "string", "minLength": 5, "maxLength": 2 }
+        }
+    }))
+    .unwrap();
+
+    let errors = schema.verify().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "properties.node");
+}
+
+#[test]
+fn test_lint_one_of() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "oneOf": [
+            { "type": "string" },
+            true,
+            false,
+            { "type": "integer" },
+            { "type": "string" }
+        ]
+    }))
+    .unwrap();
+
+    // Schemas that always match can still be the only match, unlike the ones that never do.
+    assert_eq!(
+        findings(&schema),
+        vec![
+            "oneOf.0: duplicate_one_of (warning)",
+            "oneOf.2: unreachable_one_of (warning)",
+            "oneOf.4: duplicate_one_of (warning)",
+        ]
+    );
+
+    let errors = lint(&schema);
+    let mut messages: Vec<_> = errors.iter().map(|e| e.value.to_string()).collect();
+    messages.sort();
+    assert_eq!(
+        messages,
+        [
+            r#"invalid schema: the "oneOf" subschema 0 is the same as the subschema 4"#,
+            r#"invalid schema: the "oneOf" subschema 2 can never be the only one that matches"#,
+            r#"invalid schema: the "oneOf" subschema 4 is the same as the subschema 0"#,
+        ]
+    );
+}
+
+#[test]
+fn test_lint_warnings_only() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Tree {
+        value: i32,
+        children: Vec<Tree>,
+        parent: Option<Box<Tree>>,
+    }
+
+    let schema = schemars::schema_for!(Tree);
+    assert!(lint(&schema).is_empty());
+
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "integer",
+        "enum": [1, 1.5]
+    }))
+    .unwrap();
+
+    // Warnings do not make the schema invalid.
+    assert_eq!(lint(&schema).len(), 1);
+    assert!(schema.verify().is_ok());
+}
@@ -0,0 +1,322 @@
+use schemars_crate::{self as schemars, schema_for, JsonSchema};
+use serde::Serialize;
+use serde_json::{json, Value};
+use verify::{
+    schemars::{
+        errors::ErrorValue,
+        openapi::{Body, OpenApi, OpenApiError},
+        Direction, SchemaVerifier,
+    },
+    serde::{KeySpans, Spanned},
+    Verifier,
+};
+
+fn petstore() -> OpenApi {
+    OpenApi::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/petstore.json"
+    ))
+    .unwrap()
+}
+
+fn errors(body: &Body, value: Value) -> Vec<String> {
+    match body.verify_value(&Spanned::new(&value, KeySpans::default())) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_request() {
+    let api = petstore();
+
+    let body = api
+        .request("POST", "/pets", "application/json; charset=utf-8")
+        .unwrap();
+
+    // The read-only "id" is not required in requests.
+    assert!(errors(&body, json!({ "name": "Rex", "secret": "bone" })).is_empty());
+
+    assert_eq!(
+        errors(&body, json!({ "name": "Rex" })),
+        [r#"the required property "secret" is missing"#]
+    );
+
+    // The boolean exclusiveMinimum of OpenAPI 3.0.
+    assert_eq!(
+        errors(
+            &body,
+            json!({ "name": "Rex", "secret": "bone", "price": 0.0 })
+        )
+        .len(),
+        1
+    );
+
+    assert!(matches!(
+        api.request("GET", "/pets/42", "application/json"),
+        Err(OpenApiError::NoRequestBody)
+    ));
+    assert!(matches!(
+        api.request("POST", "/pets", "text/plain"),
+        Err(OpenApiError::MediaTypeNotFound(_))
+    ));
+    assert!(matches!(
+        api.request("DELETE", "/pets", "application/json"),
+        Err(OpenApiError::OperationNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_response() {
+    let api = petstore();
+
+    let body = api
+        .response("GET", "/pets/42", 200, "application/json")
+        .unwrap();
+
+    // The write-only "secret" is not required in responses, and "tag" is nullable.
+    assert!(errors(&body, json!({ "id": 1, "name": "Rex", "tag": null })).is_empty());
+
+    assert_eq!(
+        errors(&body, json!({ "name": "Rex" })),
+        [r#"the required property "id" is missing"#]
+    );
+
+    // A referenced response.
+    let body = api
+        .response("GET", "/pets/42", 404, "application/json")
+        .unwrap();
+    assert!(errors(&body, json!({ "message": "not found" })).is_empty());
+
+    // The default response.
+    let body = api
+        .response("POST", "/pets", 500, "application/json")
+        .unwrap();
+    assert!(!errors(&body, json!({ "id": 1, "name": "Rex" })).is_empty());
+
+    // Literal path segments take precedence over templates.
+    let body = api
+        .response("GET", "/pets/mine", 200, "application/json")
+        .unwrap();
+    assert!(errors(&body, json!([{ "id": 1, "name": "Rex" }])).is_empty());
+
+    assert!(matches!(
+        api.response("GET", "/pets/42", 500, "application/json"),
+        Err(OpenApiError::StatusNotFound(500))
+    ));
+}
+
+#[test]
+fn test_version() {
+    assert!(matches!(
+        OpenApi::from_value(json!({ "swagger": "2.0" })),
+        Err(OpenApiError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn test_read_only_write_only() {
+    let api = petstore();
+
+    let body = api.request("POST", "/pets", "application/json").unwrap();
+    let value = json!({ "id": 1, "name": "Rex", "secret": "bone" });
+
+    let errors = body
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    let error = errors.iter().next().unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(error.value, ErrorValue::ReadOnly);
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "id");
+
+    let body = api
+        .response("GET", "/pets/42", 200, "application/json")
+        .unwrap();
+    let value = json!({ "id": 1, "name": "Rex", "secret": "bone" });
+
+    let errors = body
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    let error = errors.iter().next().unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(error.value, ErrorValue::WriteOnly);
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "secret");
+}
+
+// Only the schema is used.
+#[allow(dead_code)]
+#[derive(Serialize, JsonSchema)]
+struct Account {
+    #[serde(skip_deserializing)]
+    id: u64,
+    #[serde(skip_serializing)]
+    password: String,
+    name: String,
+}
+
+#[test]
+fn test_direction() {
+    let schema = schema_for!(Account);
+    let value = json!({ "id": 1, "name": "someone" });
+    let value = Spanned::new(&value, KeySpans::default());
+
+    assert!(SchemaVerifier::new(&schema)
+        .direction(Direction::Response)
+        .verify_value(&value)
+        .is_ok());
+
+    let errors = SchemaVerifier::new(&schema)
+        .direction(Direction::Request)
+        .verify_value(&value)
+        .unwrap_err();
+
+    let mut codes: Vec<_> = errors.iter().map(|e| e.value.code()).collect();
+    codes.sort_unstable();
+    assert_eq!(codes, ["read_only", "required"]);
+}
+
+#[test]
+fn test_custom_keyword() {
+    let api = OpenApi::from_value(json!({
+        "openapi": "3.0.3",
+        "paths": {
+            "/codes": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Code" }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Code": { "type": "string", "x-even-length": true }
+            }
+        }
+    }))
+    .unwrap();
+
+    let body = api.request("POST", "/codes", "application/json").unwrap();
+    assert!(errors(&body, json!("abc")).is_empty());
+
+    let body = api
+        .keyword(
+            "x-even-length",
+            |_: &Value, instance: &Value| match instance.as_str() {
+                Some(s) if s.len() % 2 != 0 => Err("the length must be even".into()),
+                _ => Ok(()),
+            },
+        )
+        .request("POST", "/codes", "application/json")
+        .unwrap();
+
+    assert_eq!(errors(&body, json!("abc")), ["the length must be even"]);
+    assert!(errors(&body, json!("ab")).is_empty());
+}
+
+#[test]
+fn test_exclusive_values() {
+    let api = OpenApi::from_value(json!({
+        "openapi": "3.0.3",
+        "paths": {
+            "/limits": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Limit" }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Limit": {
+                    "type": "object",
+                    "properties": {
+                        "value": {
+                            "type": "number",
+                            "minimum": 0,
+                            "exclusiveMinimum": true
+                        },
+                        "range": {
+                            "enum": [{ "minimum": 1, "exclusiveMinimum": true }]
+                        }
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let body = api.request("POST", "/limits", "application/json").unwrap();
+    assert!(errors(&body, json!({ "value": 1 })).is_empty());
+    assert_eq!(errors(&body, json!({ "value": 0 })).len(), 1);
+
+    // Values that look like schemas are not converted.
+    assert!(errors(
+        &body,
+        json!({ "range": { "minimum": 1, "exclusiveMinimum": true } })
+    )
+    .is_empty());
+    assert_eq!(
+        errors(&body, json!({ "range": { "exclusiveMinimum": 1 } })).len(),
+        1
+    );
+}
+
+#[test]
+fn test_read_only_ref() {
+    let api = OpenApi::from_value(json!({
+        "openapi": "3.0.3",
+        "paths": {
+            "/items": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Item" }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Id": { "type": "integer" },
+                "Item": {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "id": { "$ref": "#/components/schemas/Id", "readOnly": true }
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let body = api.request("POST", "/items", "application/json").unwrap();
+
+    // The read-only metadata next to the reference applies to the value as well.
+    assert!(errors(&body, json!({})).is_empty());
+
+    let value = json!({ "id": 1 });
+    let errors = body
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    let error = errors.iter().next().unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(error.value, ErrorValue::ReadOnly);
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "id");
+}
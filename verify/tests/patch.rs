@@ -0,0 +1,246 @@
+use schemars_crate::schema::RootSchema;
+use serde_json::json;
+use verify::{
+    schemars::{
+        errors::ErrorValue,
+        patch::{self, Operation, PatchError},
+        SchemaVerifier,
+    },
+    span::Path,
+};
+
+fn schema() -> RootSchema {
+    serde_json::from_value(json!({
+        "type": "object",
+        "required": ["name"],
+        "additionalProperties": false,
+        "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer", "minimum": 0 },
+            "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 },
+            "address": {
+                "type": "object",
+                "required": ["city"],
+                "properties": { "city": { "type": "string" } }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_apply() {
+    let document = json!({ "a": { "b": [1, 2] } });
+
+    let patch: Vec<Operation> = serde_json::from_value(json!([
+        { "op": "test", "path": "/a/b/0", "value": 1 },
+        { "op": "add", "path": "/a/b/-", "value": 3 },
+        { "op": "remove", "path": "/a/b/0" },
+        { "op": "copy", "from": "/a/b", "path": "/c" },
+        { "op": "move", "from": "/a", "path": "/d" },
+        { "op": "replace", "path": "/d/b/1", "value": "x" }
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        patch::apply(&document, &patch).unwrap(),
+        json!({ "c": [2, 3], "d": { "b": [2, "x"] } })
+    );
+
+    let err = patch::apply(
+        &document,
+        &[
+            Operation::Remove {
+                path: "/a/b/1".into(),
+            },
+            Operation::Test {
+                path: "/a/b/1".into(),
+                value: json!(2),
+            },
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        PatchError::NotFound {
+            index: 1,
+            pointer: "/a/b/1".into()
+        }
+    );
+
+    assert_eq!(
+        patch::merge(
+            &json!({ "a": 1, "b": { "c": 2, "d": 3 } }),
+            &json!({ "a": null, "b": { "c": 4 }, "e": [] })
+        ),
+        json!({ "b": { "c": 4, "d": 3 }, "e": [] })
+    );
+}
+
+#[test]
+fn test_verify_patch() {
+    let root = schema();
+    let verifier = SchemaVerifier::new(&root);
+
+    // The document is already invalid, but not where the patch changes it.
+    let document = json!({ "name": "a", "age": -1, "tags": ["x"] });
+
+    let patch = [Operation::Add {
+        path: "/tags/-".into(),
+        value: json!("y"),
+    }];
+    assert_eq!(
+        patch::verify_patch(&verifier, &document, &patch).unwrap(),
+        json!({ "name": "a", "age": -1, "tags": ["x", "y"] })
+    );
+
+    let patch = [
+        Operation::Add {
+            path: "/tags/-".into(),
+            value: json!(1),
+        },
+        Operation::Add {
+            path: "/tags/-".into(),
+            value: json!("z"),
+        },
+        Operation::Remove {
+            path: "/name".into(),
+        },
+    ];
+    let errors = patch::verify_patch(&verifier, &document, &patch).unwrap_err();
+    let mut codes = errors
+        .iter()
+        .map(|e| {
+            (
+                e.span.clone().unwrap_or_default().json_pointer(),
+                e.value.code(),
+            )
+        })
+        .collect::<Vec<_>>();
+    codes.sort();
+    assert_eq!(
+        codes,
+        vec![
+            ("".into(), "required"),
+            ("/tags".into(), "max_items"),
+            ("/tags/1".into(), "invalid_type"),
+        ]
+    );
+
+    let patch = [
+        Operation::Replace {
+            path: "/name".into(),
+            value: json!("b"),
+        },
+        Operation::Add {
+            path: "/nickname".into(),
+            value: json!("b"),
+        },
+        Operation::Add {
+            path: "/address/zip".into(),
+            value: json!("1234"),
+        },
+    ];
+    assert_eq!(patch::disallowed(&root, &patch), vec![1]);
+
+    let errors = patch::verify_patch(&verifier, &document, &patch).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.value, ErrorValue::UnknownProperty);
+    assert_eq!(error.span, Some(Path::new() + "nickname"));
+
+    let patch = [Operation::Remove {
+        path: "/age/x".into(),
+    }];
+    let errors = patch::verify_patch(&verifier, &document, &patch).unwrap_err();
+    assert_eq!(errors.iter().next().unwrap().value.code(), "custom");
+}
+
+#[test]
+fn test_verify_merge_patch() {
+    let root = schema();
+    let verifier = SchemaVerifier::new(&root);
+
+    let document = json!({ "name": "a", "age": -1 });
+
+    assert!(patch::verify_merge_patch(&verifier, &document, &json!({ "tags": ["x"] })).is_ok());
+
+    let errors = patch::verify_merge_patch(
+        &verifier,
+        &document,
+        &json!({ "name": null, "address": { "city": 1 } }),
+    )
+    .unwrap_err();
+
+    let mut codes = errors
+        .iter()
+        .map(|e| {
+            (
+                e.span.clone().unwrap_or_default().json_pointer(),
+                e.value.code(),
+            )
+        })
+        .collect::<Vec<_>>();
+    codes.sort();
+    assert_eq!(
+        codes,
+        vec![
+            ("".into(), "required"),
+            ("/address/city".into(), "invalid_type")
+        ]
+    );
+
+    // An empty patch touches nothing, not even the document itself.
+    let document = json!({ "age": -1 });
+    assert!(patch::verify_merge_patch(&verifier, &document, &json!({})).is_ok());
+
+    // The document is already invalid, the errors of the parents
+    // are only returned if they are about the touched properties.
+    let document = json!({ "age": -1, "address": { "street": "b" } });
+    assert!(patch::verify_merge_patch(&verifier, &document, &json!({ "tags": ["x"] })).is_ok());
+    assert!(patch::verify_merge_patch(
+        &verifier,
+        &document,
+        &json!({ "address": { "street": "c" } })
+    )
+    .is_ok());
+
+    let document = json!({ "name": "a", "address": { "city": "b" } });
+    let errors = patch::verify_merge_patch(
+        &verifier,
+        &document,
+        &json!({ "address": { "city": null } }),
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.value.code(), "required");
+    assert_eq!(error.span, Some(Path::new() + "address"));
+}
+
+#[test]
+fn test_custom_keyword() {
+    let root: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "string", "x-upper": true }
+        }
+    }))
+    .unwrap();
+
+    let verifier = SchemaVerifier::new(&root).keyword(
+        "x-upper",
+        |_: &serde_json::Value, instance: &serde_json::Value| match instance.as_str() {
+            Some(s) if s.to_uppercase() != s => Err("must be upper case".into()),
+            _ => Ok(()),
+        },
+    );
+
+    let document = json!({ "code": "AB" });
+
+    assert!(patch::verify_merge_patch(&verifier, &document, &json!({ "code": "CD" })).is_ok());
+
+    let errors =
+        patch::verify_merge_patch(&verifier, &document, &json!({ "code": "cd" })).unwrap_err();
+    assert_eq!(errors.iter().next().unwrap().value.code(), "keyword");
+}
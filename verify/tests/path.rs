@@ -0,0 +1,66 @@
+use serde_json::json;
+use verify::{
+    adhoc::{AdHoc, Failures},
+    serde::{PathSpans, Spanned},
+    span::{Path, Segment},
+    Verifier,
+};
+
+#[test]
+fn test_renderings() {
+    let path = Path::new() + "items" + 0 + "a.b" + "it's" + "0";
+
+    assert_eq!(path.json_pointer(), "/items/0/a.b/it's/0");
+    assert_eq!(path.json_path(), r#"$.items[0]['a.b']['it\'s']['0']"#);
+    assert_eq!(path.dotted(), r#"items[0].a\.b.it's.0"#);
+
+    assert_eq!(Path::from_json_path(&path.json_path()).unwrap(), path);
+    assert_eq!(Path::from_dotted(&path.dotted()).unwrap(), path);
+
+    // The key "0" becomes an index.
+    let pointer = Path::from_json_pointer(&path.json_pointer()).unwrap();
+    assert_eq!(pointer.iter().last(), Some(&Segment::Index(0)));
+
+    let escaped = Path::new() + "a/b" + "~";
+    assert_eq!(escaped.json_pointer(), "/a~1b/~0");
+    assert_eq!(Path::from_json_pointer("/a~1b/~0").unwrap(), escaped);
+
+    let indices = Path::new() + 1 + 2 + "" + "x";
+    assert_eq!(indices.dotted(), "[1][2]..x");
+    assert_eq!(Path::from_dotted("[1][2]..x").unwrap(), indices);
+
+    assert_eq!(
+        Path::from_json_path(r#"$["first name"].tags[10]"#).unwrap(),
+        Path::new() + "first name" + "tags" + 10
+    );
+
+    assert!(Path::from_json_path("items").is_err());
+    assert!(Path::from_json_path("$[01]").is_err());
+    assert!(Path::from_dotted("a[0]b").is_err());
+    assert_eq!(Path::from_json_pointer("/a~2").unwrap_err().position(), 2);
+    assert_eq!(Path::from_json_pointer("").unwrap(), Path::new());
+}
+
+#[test]
+fn test_spans() {
+    let verifier: AdHoc<Path> = AdHoc::new().on_u64(|_| Err("expected a string".into()));
+
+    let value = json!({ "items": [{ "name": "a" }, { "name": 2 }] });
+
+    let errors: Failures<Path> = verifier
+        .verify_value(&Spanned::new(&value, PathSpans::default()))
+        .unwrap_err();
+
+    let span = errors.0[0].span.clone().unwrap();
+    assert_eq!(span.json_pointer(), "/items/1/name");
+    assert_eq!(span.resolve(&value), Some(&json!(2)));
+
+    assert_eq!(
+        serde_json::to_value(&span).unwrap(),
+        json!(["items", 1, "name"])
+    );
+    assert_eq!(
+        serde_json::from_value::<Path>(json!(["items", 1, "name"])).unwrap(),
+        span
+    );
+}
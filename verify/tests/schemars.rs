@@ -1,9 +1,22 @@
 // TODO: proper tests
-use schemars_crate::{self as schemars, schema::RootSchema, JsonSchema};
+use schemars_crate::{
+    self as schemars,
+    schema::{ArrayValidation, InstanceType, RootSchema, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::Serialize;
 use serde_json::json;
 use verify::{
+    schemars::{
+        errors::{ErrorValue, Errors, InvalidSchema, UnsupportedValue},
+        keywords,
+        messages::{Catalog, English},
+        rules,
+        SchemaVerifier,
+        SubschemaExt,
+    },
     serde::{KeySpans, Spanned},
+    span::{Keys, Span},
     Verifier, Verify,
 };
 
@@ -139,3 +152,871 @@ fn test_self_verify() {
         }
     }
 }
+
+#[test]
+fn test_enum_composite() {
+    let schema_value = json! {
+        {
+            "type": "object",
+            "properties": {
+              "point": {
+                "type": "object",
+                "enum": [{ "x": 1, "y": 2 }, { "x": 0, "y": 0 }]
+              },
+              "pair": {
+                "type": "array",
+                "const": [1, "a"]
+              }
+            }
+          }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let valid = json! {
+        {
+            "point": { "y": 2.0, "x": 1 },
+            "pair": [1.0, "a"]
+        }
+    };
+
+    assert!(schema
+        .verify_value(&Spanned::new(&valid, KeySpans::default()))
+        .is_ok());
+
+    let invalid = json! {
+        {
+            "point": { "x": 1, "y": 3 },
+            "pair": ["a", 1]
+        }
+    };
+
+    let errors = schema
+        .verify_value(&Spanned::new(&invalid, KeySpans::default()))
+        .unwrap_err();
+
+    let mut spans: Vec<String> = errors
+        .into_iter()
+        .map(|e| e.span.map(|s| s.dotted()).unwrap_or_default())
+        .collect();
+    spans.sort();
+
+    assert_eq!(spans, vec!["pair", "point"]);
+}
+
+/// A map that validates a value without a key first.
+struct KeylessMap;
+
+impl verify::span::Spanned for KeylessMap {
+    type Span = Keys;
+
+    fn span(&self) -> Option<Self::Span> {
+        None
+    }
+}
+
+impl verify::Validate for KeylessMap {
+    fn validate<V: verify::Validator<Self::Span>>(&self, validator: V) -> Result<(), V::Error> {
+        use verify::ValidateMap;
+
+        let mut map = validator.validate_map(Some(1))?;
+        map.validate_value(&Spanned::new(&json!(1), KeySpans::default()))?;
+        map.end()
+    }
+}
+
+#[test]
+fn test_enum_keyless_map() {
+    let schema_value = json! {
+        {
+            "enum": [{ "a": 1 }]
+        }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let errors = schema.verify_value(&KeylessMap).unwrap_err();
+
+    assert!(errors
+        .iter()
+        .any(|e| matches!(e.value, ErrorValue::InvalidEnumValue { .. })));
+
+    // Only scalars are expected, objects are not collected at all.
+    let schema_value = json! {
+        {
+            "enum": [1, "a"]
+        }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    assert!(schema.verify_value(&KeylessMap).is_err());
+    assert!(schema
+        .verify_value(&Spanned::new(&json!(1.0), KeySpans::default()))
+        .is_ok());
+    assert!(schema
+        .verify_value(&Spanned::new(&json!(["a"]), KeySpans::default()))
+        .is_err());
+}
+
+#[test]
+fn test_unique_items() {
+    let schema_value = json! {
+        {
+            "type": "array",
+            "uniqueItems": true
+        }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let valid = json! { [1, 2, "1", [1, 2], [2, 1], { "a": 1 }, { "a": 2 }] };
+
+    assert!(schema
+        .verify_value(&Spanned::new(&valid, KeySpans::default()))
+        .is_ok());
+
+    let invalid = json! { [1, 1.0, { "a": 1, "b": 2 }, { "b": 2, "a": 1.0 }] };
+
+    let errors = schema
+        .verify_value(&Spanned::new(&invalid, KeySpans::default()))
+        .unwrap_err();
+
+    let spans: Vec<String> = errors
+        .into_iter()
+        .map(|e| e.span.map(|s| s.dotted()).unwrap_or_default())
+        .collect();
+
+    assert_eq!(spans, vec!["1", "3"]);
+}
+
+struct Shouting;
+
+impl Catalog for Shouting {
+    fn error_value<S: Span>(
+        &self,
+        error: &ErrorValue<S>,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match error {
+            ErrorValue::LessThanExpected { min, .. } => write!(f, "AT LEAST {}", min),
+            _ => English.error_value(error, f),
+        }
+    }
+
+    fn invalid_schema(
+        &self,
+        error: &InvalidSchema,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        English.invalid_schema(error, f)
+    }
+
+    fn unsupported_value(
+        &self,
+        error: &UnsupportedValue,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        English.unsupported_value(error, f)
+    }
+}
+
+#[test]
+fn test_localized_messages() {
+    let schema_value = json! {
+        {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+              "age": {
+                "type": "integer",
+                "minimum": 18
+              }
+            }
+          }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let errors = schema
+        .verify_value(&Spanned::new(&json!({ "age": 3 }), KeySpans::default()))
+        .unwrap_err();
+
+    assert_eq!(
+        errors.localized(&Shouting).to_string(),
+        "AT LEAST 18\nthe required property \"name\" is missing\n"
+    );
+    assert_eq!(
+        errors.to_string(),
+        "the value is expected to be at least 18\nthe required property \"name\" is missing\n"
+    );
+}
+
+#[test]
+fn test_error_message() {
+    let schema_value = json! {
+        {
+            "type": "object",
+            "required": ["username", "name"],
+            "errorMessage": {
+                "required": { "name": "Name is required" }
+            },
+            "properties": {
+              "username": {
+                "type": "string",
+                "pattern": "^[a-zA-Z]+$",
+                "minLength": 3,
+                "errorMessage": {
+                    "pattern": "Username must be 3-20 letters",
+                    "minLength": "Username must be 3-20 letters"
+                }
+              },
+              "age": {
+                "type": "integer",
+                "errorMessage": "Age must be a whole number"
+              }
+            }
+          }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "username": "a1", "age": 1.5 }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "Age must be a whole number",
+            "Username must be 3-20 letters",
+            "Username must be 3-20 letters",
+            "Name is required",
+        ]
+    );
+
+    assert!(matches!(
+        errors.iter().nth(1).unwrap().value,
+        ErrorValue::NoPatternMatch { .. }
+    ));
+}
+
+fn luhn(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        let mut d = match c.to_digit(10) {
+            Some(d) => d,
+            None => return false,
+        };
+        if i % 2 == 1 {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    sum % 10 == 0
+}
+
+#[test]
+fn test_custom_keyword() {
+    let schema_value = json! {
+        {
+            "type": "object",
+            "properties": {
+              "card": {
+                "type": "string",
+                "x-luhn": true
+              }
+            }
+          }
+    };
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let verifier = SchemaVerifier::new(&schema).keyword(
+        "x-luhn",
+        |enabled: &serde_json::Value, instance: &serde_json::Value| match instance.as_str() {
+            Some(digits) if enabled.as_bool() == Some(true) && !luhn(digits) => {
+                Err("invalid card number".into())
+            }
+            _ => Ok(()),
+        },
+    );
+
+    assert!(verifier
+        .verify_value(&Spanned::new(
+            &json!({ "card": "4111111111111111" }),
+            KeySpans::default()
+        ))
+        .is_ok());
+
+    let errors = verifier
+        .verify_value(&Spanned::new(
+            &json!({ "card": "4111111111111112" }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "card");
+    assert_eq!(error.to_string(), "invalid card number");
+
+    // Without the handler the keyword is ignored.
+    assert!(schema
+        .verify_value(&Spanned::new(
+            &json!({ "card": "4111111111111112" }),
+            KeySpans::default()
+        ))
+        .is_ok());
+}
+
+#[test]
+fn test_custom_keyword_subschema() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "properties": {
+            "card": { "$ref": "#/definitions/Card" }
+        },
+        "definitions": {
+            "Card": { "type": "string", "x-luhn": true }
+        }
+    }))
+    .unwrap();
+
+    let luhn_keyword = |_: &serde_json::Value, instance: &serde_json::Value| match instance.as_str() {
+        Some(digits) if !luhn(digits) => Err("invalid card number".to_string()),
+        _ => Ok(()),
+    };
+
+    let card = json!("4111111111111112");
+    let invalid = Spanned::new(&card, KeySpans::default());
+
+    // The subschemas of the verifier use its keywords.
+    let verifier = SchemaVerifier::new(&schema).keyword("x-luhn", luhn_keyword);
+    assert!(verifier
+        .subschema("#/definitions/Card")
+        .unwrap()
+        .verify_value(&invalid)
+        .is_err());
+
+    assert!(schema
+        .subschema("#/definitions/Card")
+        .unwrap()
+        .keyword("x-luhn", luhn_keyword)
+        .verify_value(&invalid)
+        .is_err());
+
+    // Without the handler the keyword is ignored.
+    assert!(schema
+        .subschema("#/definitions/Card")
+        .unwrap()
+        .verify_value(&invalid)
+        .is_ok());
+
+    // Plain schemas and rules never use custom keywords.
+    assert!(schema.definitions["Card"].verify_value(&invalid).is_ok());
+    assert!(rules::object()
+        .field("card", schema.definitions["Card"].clone().into_object())
+        .verify_value(&Spanned::new(
+            &json!({ "card": "4111111111111112" }),
+            KeySpans::default()
+        ))
+        .is_ok());
+}
+
+#[test]
+fn test_partial() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "required": ["name", "address"],
+        "properties": {
+            "name": { "type": "string" },
+            "address": {
+                "type": "object",
+                "required": ["city"],
+                "properties": { "city": { "type": "string" } }
+            }
+        }
+    }))
+    .unwrap();
+
+    let value = json!({ "address": {} });
+    let value = Spanned::new(&value, KeySpans::default());
+
+    let errors = SchemaVerifier::new(&schema)
+        .verify_value(&value)
+        .unwrap_err();
+    assert_eq!(errors.len(), 2);
+
+    let verifier = SchemaVerifier::new(&schema).partial(true);
+    assert!(verifier.verify_value(&value).is_ok());
+
+    // Everything else is still validated.
+    let value = json!({ "address": { "city": 1 } });
+    assert!(verifier
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .is_err());
+}
+
+#[test]
+fn test_warnings() {
+    #[derive(Serialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Update {
+        name: String,
+        #[deprecated]
+        nickname: Option<String>,
+    }
+
+    #[allow(deprecated)]
+    let schema = schemars::schema_for!(Update);
+
+    let verifier = SchemaVerifier::new(&schema).keyword(
+        "x-soft-max-length",
+        keywords::Warn(|max: &serde_json::Value, instance: &serde_json::Value| {
+            match (max.as_u64(), instance.as_str()) {
+                (Some(max), Some(s)) if s.chars().count() as u64 > max => {
+                    Err("the value is too long".into())
+                }
+                _ => Ok(()),
+            }
+        }),
+    );
+
+    let value = json!({ "name": "name", "nickname": "nick" });
+    let value = Spanned::new(&value, KeySpans::default());
+
+    // Only warnings, so the value is valid.
+    assert!(verifier.verify_value(&value).is_ok());
+
+    let warnings: Errors<Keys> = verifier.verify_with_warnings(&value).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings.iter().all(|w| w.is_warning()));
+
+    let warning = warnings.iter().next().unwrap();
+    assert_eq!(warning.value, ErrorValue::Deprecated);
+    assert_eq!(warning.span.as_ref().unwrap().dotted(), "nickname");
+
+    let mut schema = schema;
+    schema
+        .schema
+        .extensions
+        .insert("x-soft-max-length".into(), json!(2));
+    let verifier = SchemaVerifier::new(&schema).keyword(
+        "x-soft-max-length",
+        keywords::Warn(|_: &serde_json::Value, _: &serde_json::Value| Err("too long".into())),
+    );
+
+    // Errors come first, then the warnings.
+    let value = json!({ "nickname": "nick" });
+    let errors: Errors<Keys> = verifier
+        .verify_with_warnings(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    assert!(errors.has_errors());
+    assert!(matches!(
+        errors.iter().next().unwrap().value,
+        ErrorValue::RequiredProperty { .. }
+    ));
+    assert_eq!(errors.warnings().count(), 2);
+
+    // Warnings of subschemas that did not match are not reported.
+    let schema: RootSchema = serde_json::from_value(json!({
+        "anyOf": [
+            { "type": "string", "deprecated": true },
+            { "type": "integer" }
+        ]
+    }))
+    .unwrap();
+    let value = json!(1);
+    let warnings: Errors<Keys> = SchemaVerifier::new(&schema)
+        .verify_with_warnings(&Spanned::new(&value, KeySpans::default()))
+        .unwrap();
+    assert!(warnings.is_empty());
+
+    let serialized = serde_json::to_value(
+        SchemaVerifier::new(&schema)
+            .verify_with_warnings::<Keys, _>(&Spanned::new(&json!("a"), KeySpans::default()))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        serialized,
+        json!([{
+            "meta": { "deprecated": true },
+            "span": null,
+            "code": "deprecated",
+            "severity": "warning"
+        }])
+    );
+}
+
+#[test]
+fn test_subschema() {
+    let schema: RootSchema = serde_json::from_value(json!({
+        "type": "object",
+        "properties": {
+            "addresses": { "type": "array", "items": { "$ref": "#/definitions/Address" } }
+        },
+        "definitions": {
+            "Address": {
+                "type": "object",
+                "required": ["city"],
+                "properties": {
+                    "city": { "type": "string" },
+                    "country": { "$ref": "#/definitions/Country" }
+                }
+            },
+            "Country": { "type": "string", "enum": ["HU", "NL"] }
+        }
+    }))
+    .unwrap();
+
+    let address = schema.subschema("#/definitions/Address").unwrap();
+
+    let value = json!({ "city": "Budapest", "country": "HU" });
+    assert!(address
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .is_ok());
+
+    // References are resolved against the root.
+    let value = json!({ "city": "Budapest", "country": "XX" });
+    let errors: Errors<Keys> = address
+        .verify_value(&Spanned::new(&value, KeySpans::default()))
+        .unwrap_err();
+    assert_eq!(errors.iter().next().unwrap().value.code(), "enum");
+
+    let items = schema.subschema("#/properties/addresses/items").unwrap();
+    assert!(items
+        .verify_value(&Spanned::new(&json!({}), KeySpans::default()))
+        .is_err());
+
+    assert!(schema
+        .subschema("#/components/schemas/Country/enum")
+        .is_none());
+    assert!(schema.subschema("#/definitions/Missing").is_none());
+
+    // Plain schemas have no definitions.
+    let country = schema.definitions["Country"].clone();
+    assert!(country
+        .verify_value(&Spanned::new(&json!("NL"), KeySpans::default()))
+        .is_ok());
+
+    let city = schema.definitions["Address"]
+        .subschema("#/properties/city")
+        .unwrap();
+    assert!(city
+        .verify_value(&Spanned::new(&json!(1), KeySpans::default()))
+        .is_err());
+}
+
+#[test]
+fn test_serialize_errors() {
+    let schema_value = json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string", "maxLength": 3 },
+            "tags": { "type": "array", "maxItems": 1 }
+        },
+        "required": ["id"]
+    });
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "name": "abcd", "tags": ["a", "b"] }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    let mut codes: Vec<_> = errors.iter().map(|e| e.value.code()).collect();
+    codes.sort_unstable();
+    assert_eq!(codes, ["max_items", "max_length", "required"]);
+
+    let serialized = serde_json::to_value(&errors).unwrap();
+    let name_error = serialized
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["code"] == "max_length")
+        .unwrap();
+
+    assert_eq!(
+        name_error,
+        &json!({
+            "span": ["name"],
+            "code": "max_length",
+            "params": { "max_length": 3 }
+        })
+    );
+
+    let deserialized: Errors<Keys> = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized, errors);
+}
+
+#[test]
+fn test_best_match() {
+    let schema_value = json!({
+        "oneOf": [
+            {
+                "type": "object",
+                "properties": {
+                    "kind": { "type": "string", "enum": ["circle"] },
+                    "radius": { "type": "number" }
+                },
+                "required": ["kind", "radius"]
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "kind": { "type": "string", "enum": ["square"] },
+                    "side": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["kind", "side"]
+            },
+            { "type": "string" }
+        ]
+    });
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "kind": "square", "side": 0 }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.iter().next().unwrap().value.code(), "none_valid");
+
+    let best = errors.best_match();
+    assert_eq!(best.len(), 1);
+
+    let error = best.iter().next().unwrap();
+    assert_eq!(error.value.code(), "minimum");
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "side");
+
+    assert_eq!(
+        errors.localized(&English).best_match().to_string(),
+        "the value is expected to be at least 1\n"
+    );
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(tag = "type")]
+#[allow(dead_code)]
+enum Shape {
+    Circle { radius: f64 },
+    Square { side: u32 },
+    Point,
+}
+
+#[test]
+fn test_discriminator() {
+    let schema = schemars::schema_for!(Shape);
+
+    assert!(schema
+        .verify_value(&Spanned::new(
+            &json!({ "type": "Square", "side": 2 }),
+            KeySpans::default()
+        ))
+        .is_ok());
+
+    // Only the selected variant is validated.
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "type": "Square", "side": "2" }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.value.code(), "invalid_type");
+    assert_eq!(error.span.as_ref().unwrap().dotted(), "side");
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "type": "Triangle" }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors.iter().next().unwrap().to_string(),
+        r#"unknown variant "Triangle" of "type", expected to be one of {"Circle", "Square", "Point"}"#
+    );
+}
+
+#[test]
+fn test_discriminator_items() {
+    let shape = schemars::schema_for!(Shape);
+
+    let schema = RootSchema {
+        schema: SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(Schema::Object(shape.schema).into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let shapes = vec![
+        Shape::Circle { radius: 1.0 },
+        Shape::Square { side: 2 },
+        Shape::Point,
+    ];
+
+    assert!(schema
+        .verify_value(&Spanned::new(&shapes, KeySpans::default()))
+        .is_ok());
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!([
+                { "type": "Point" },
+                { "type": "Square", "side": -1 },
+                { "side": 1, "type": "Circle", "radius": 1 }
+            ]),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors.iter().next().unwrap().span.as_ref().unwrap().dotted(),
+        "1.side"
+    );
+}
+
+#[test]
+fn test_openapi_discriminator() {
+    let schema_value = json!({
+        "oneOf": [
+            { "$ref": "#/definitions/Cat" },
+            { "$ref": "#/definitions/Dog" }
+        ],
+        "discriminator": {
+            "propertyName": "pet",
+            "mapping": { "cat": "#/definitions/Cat" }
+        },
+        "definitions": {
+            "Cat": {
+                "type": "object",
+                "properties": { "lives": { "type": "integer", "maximum": 9 } }
+            },
+            "Dog": {
+                "type": "object",
+                "properties": { "good": { "type": "boolean" } }
+            }
+        }
+    });
+
+    let schema = serde_json::from_value::<RootSchema>(schema_value).unwrap();
+
+    assert!(schema
+        .verify_value(&Spanned::new(
+            &json!({ "pet": "Dog", "good": true }),
+            KeySpans::default()
+        ))
+        .is_ok());
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "pet": "cat", "lives": 10 }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.iter().next().unwrap().value.code(), "maximum");
+
+    let errors = schema
+        .verify_value(&Spanned::new(
+            &json!({ "pet": "Cat" }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    assert_eq!(errors.iter().next().unwrap().value.code(), "unknown_variant");
+}
+
+#[test]
+fn test_rules() {
+    let config = rules::object()
+        .field("host", rules::string().len(1..))
+        .field("port", rules::int().range(1..=65535))
+        .optional("tags", rules::array(rules::string()).unique())
+        .deny_unknown();
+
+    assert!(config
+        .verify_value(&Spanned::new(
+            &json!({ "host": "localhost", "port": 8080, "tags": ["a", "b"] }),
+            KeySpans::default()
+        ))
+        .is_ok());
+
+    let errors: Errors<_> = config
+        .verify_value(&Spanned::new(
+            &json!({ "host": "", "port": 0, "tags": ["a", "a"], "debug": true }),
+            KeySpans::default(),
+        ))
+        .unwrap_err();
+
+    let mut errors: Vec<_> = errors
+        .iter()
+        .map(|e| (e.value.code(), e.span.as_ref().unwrap().dotted()))
+        .collect();
+    errors.sort();
+
+    assert_eq!(
+        errors,
+        [
+            ("min_length", "host".to_string()),
+            ("minimum", "port".to_string()),
+            ("unique_items", "tags.1".to_string()),
+            ("unknown_property", "debug".to_string()),
+        ]
+    );
+
+    let schema = serde_json::to_value(config.root_schema()).unwrap();
+    assert_eq!(schema["properties"]["port"]["maximum"], json!(65535.0));
+    assert_eq!(schema["additionalProperties"], json!(false));
+}
+
+#[test]
+fn test_integer_is_number() {
+    let price = rules::number().range(0.0..);
+
+    assert_eq!(
+        serde_json::to_value(price.root_schema().schema).unwrap()["type"],
+        json!("number")
+    );
+
+    for value in &[json!(1), json!(1.5), json!(0)] {
+        assert!(price
+            .verify_value(&Spanned::new(value, KeySpans::default()))
+            .is_ok());
+    }
+    assert!(price
+        .verify_value(&Spanned::new(&2u32, KeySpans::default()))
+        .is_ok());
+
+    assert!(price
+        .verify_value(&Spanned::new(&json!(-1), KeySpans::default()))
+        .is_err());
+    assert!(rules::int()
+        .verify_value(&Spanned::new(&json!(1.5), KeySpans::default()))
+        .is_err());
+}
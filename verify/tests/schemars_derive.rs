@@ -1,6 +1,12 @@
 use schemars_crate::{self as schemars, JsonSchema};
 use serde::Serialize;
-use verify::Verify;
+use std::sync::Arc;
+use verify::{
+    adhoc::SpannedError,
+    schemars::{cached_schema, errors::Errors},
+    span::Keys,
+    Verify,
+};
 
 #[derive(Default, Verify, Serialize, JsonSchema)]
 #[verify(schemars, serde)]
@@ -20,6 +26,48 @@ struct SomeStructExplicit {
     some_value: i32,
 }
 
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(
+    schemars,
+    serde,
+    check = "start_before_end",
+    check = "exactly_one_contact"
+)]
+struct Booking {
+    #[schemars(range(max = 24))]
+    start: u32,
+    end: u32,
+    email: Option<String>,
+    phone: Option<String>,
+}
+
+#[allow(clippy::result_large_err)]
+fn start_before_end(booking: &Booking) -> Result<(), Errors<Keys>> {
+    if booking.start >= booking.end {
+        return Err(Errors::spanned(
+            Some(Keys::from("end".to_string())),
+            "the end must be after the start".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn exactly_one_contact(booking: &Booking) -> Result<(), Errors<Keys>> {
+    if booking.email.is_some() == booking.phone.is_some() {
+        let mut errors = Errors::spanned(
+            Some(Keys::from("email".to_string())),
+            "exactly one of email and phone must be set".into(),
+        );
+        errors += Errors::spanned(
+            Some(Keys::from("phone".to_string())),
+            "exactly one of email and phone must be set".into(),
+        );
+        return Err(errors);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_verify() {
     let some_struct = SomeStruct::default();
@@ -28,3 +76,203 @@ fn test_verify() {
     let some_struct_explicit = SomeStructExplicit::default();
     assert!(some_struct_explicit.verify().is_ok());
 }
+
+#[test]
+fn test_checks() {
+    let booking = Booking {
+        start: 10,
+        end: 12,
+        email: Some("someone@example.com".into()),
+        phone: None,
+    };
+    assert!(booking.verify().is_ok());
+
+    let booking = Booking {
+        start: 30,
+        end: 12,
+        email: None,
+        phone: None,
+    };
+
+    let mut errors: Vec<_> = booking
+        .verify()
+        .unwrap_err()
+        .iter()
+        .map(|e| e.span.as_ref().unwrap().dotted())
+        .collect();
+    errors.sort();
+
+    // The schema error for "start" is merged with the errors of the checks.
+    assert_eq!(errors, ["email", "end", "phone", "start"]);
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde)]
+struct Wrapper<T> {
+    #[schemars(length(max = 2))]
+    items: Vec<T>,
+}
+
+#[test]
+fn test_cached_schema() {
+    assert!(Arc::ptr_eq(
+        &cached_schema::<Wrapper<i32>>(),
+        &cached_schema::<Wrapper<i32>>()
+    ));
+
+    // Every set of type parameters has its own schema.
+    assert!(!Arc::ptr_eq(
+        &cached_schema::<Wrapper<i32>>(),
+        &cached_schema::<Wrapper<String>>()
+    ));
+
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                assert!(Wrapper { items: vec![i] }.verify().is_ok());
+                assert!(Wrapper {
+                    items: vec![i.to_string(); 3]
+                }
+                .verify()
+                .is_err());
+            })
+        })
+        .collect();
+
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Address {
+    #[schemars(length(min = 1))]
+    city: String,
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars(settings = "openapi3"), serde)]
+struct Customer {
+    name: Option<String>,
+    address: Option<Address>,
+}
+
+#[test]
+fn test_openapi3_settings() {
+    let schema = verify::schemars::cached_schema_with::<Customer, _>(
+        schemars::gen::SchemaSettings::openapi3,
+    );
+
+    // The settings are cached separately.
+    assert!(!Arc::ptr_eq(&schema, &cached_schema::<Customer>()));
+
+    let address =
+        serde_json::to_value(&schema.schema.object.as_ref().unwrap().properties["address"])
+            .unwrap();
+    assert_eq!(address["nullable"], true);
+    assert_eq!(address["allOf"][0]["$ref"], "#/components/schemas/Address");
+
+    let customer = Customer {
+        name: None,
+        address: None,
+    };
+    assert!(customer.verify().is_ok());
+
+    let customer = Customer {
+        name: Some("someone".into()),
+        address: Some(Address { city: "".into() }),
+    };
+
+    let errors = customer.verify().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors
+            .iter()
+            .next()
+            .unwrap()
+            .span
+            .as_ref()
+            .unwrap()
+            .dotted(),
+        "address.city"
+    );
+}
+
+// Types with the same schema id, which is possible with manual implementations.
+macro_rules! same_name {
+    ($ty:ident, $value:ty, $max:tt) => {
+        #[derive(Verify, Serialize)]
+        #[verify(schemars, serde)]
+        struct $ty {
+            value: $value,
+        }
+
+        impl JsonSchema for $ty {
+            fn schema_name() -> String {
+                "Item".into()
+            }
+
+            fn schema_id() -> std::borrow::Cow<'static, str> {
+                "Item".into()
+            }
+
+            fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                serde_json::from_value(serde_json::json!({
+                    "type": "object",
+                    "properties": { "value": $max }
+                }))
+                .unwrap()
+            }
+        }
+    };
+}
+
+same_name!(FirstItem, u32, { "type": "integer", "maximum": 1 });
+same_name!(SecondItem, String, { "type": "string", "maxLength": 1 });
+
+#[test]
+fn test_cached_schema_same_name() {
+    assert_eq!(FirstItem::schema_id(), SecondItem::schema_id());
+    assert!(!Arc::ptr_eq(
+        &cached_schema::<FirstItem>(),
+        &cached_schema::<SecondItem>()
+    ));
+
+    assert!(FirstItem { value: 1 }.verify().is_ok());
+    assert!(SecondItem { value: "a".into() }.verify().is_ok());
+    assert!(FirstItem { value: 2 }.verify().is_err());
+    assert!(SecondItem { value: "ab".into() }.verify().is_err());
+}
+
+#[test]
+fn test_cached_schema_settings_pointer() {
+    let settings: fn() -> schemars::gen::SchemaSettings = schemars::gen::SchemaSettings::openapi3;
+
+    // Function pointers are not told apart, so their schemas are not cached.
+    assert!(!Arc::ptr_eq(
+        &verify::schemars::cached_schema_with::<Customer, _>(settings),
+        &verify::schemars::cached_schema_with::<Customer, _>(settings)
+    ));
+}
+
+#[derive(Verify, Serialize, JsonSchema)]
+#[verify(schemars, serde)]
+struct Borrowed<'a> {
+    #[schemars(length(max = 2))]
+    name: &'a str,
+}
+
+#[test]
+fn test_borrowed() {
+    let name = String::from("ab");
+    assert!(Borrowed { name: &name }.verify().is_ok());
+    assert!(Borrowed { name: "abc" }.verify().is_err());
+
+    // Generic types are verified with type parameters that are not 'static.
+    fn verify_borrowed<'a>(items: Vec<&'a str>) -> bool {
+        Wrapper::<&'a str> { items }.verify().is_ok()
+    }
+
+    assert!(verify_borrowed(vec![&name]));
+    assert!(!verify_borrowed(vec![&name; 3]));
+}